@@ -0,0 +1,274 @@
+use std::{process::exit, str::FromStr};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use crate::{
+    config::Config,
+    server::{Entries, LookUp, MigrationStatus, OpenOptions, State},
+};
+
+pub(crate) struct Args {
+    pub(crate) config: Option<String>,
+    pub(crate) no_migrate: bool,
+    pub(crate) read_only: bool,
+    pub(crate) command: Command,
+}
+
+pub(crate) enum Command {
+    Serve,
+    Migrate { dry_run: bool },
+    CheckConfig,
+    Store(StoreCommand),
+}
+
+/// A command that runs on the store, opened without migrating it.
+pub(crate) enum StoreCommand {
+    Dump(LookUp),
+    BackupCreate { keep: Option<u32> },
+    BackupList,
+    BackupRestore(u32),
+    BackupPurge { keep: u32 },
+    BackupVerify,
+}
+
+const COMMANDS: &[&str] = &["serve", "dump", "backup", "migrate", "check-config"];
+
+fn usage() {
+    println!("{} {}", package(), version());
+    println!();
+    println!(
+        "USAGE: \
+        \n    {} [FLAGS] [CONFIG] [COMMAND] \
+        \n \
+        \nFLAGS: \
+        \n    -h, --help              Prints help information \
+        \n    -V, --version           Prints version information \
+        \n    -c, --config <CONFIG>   A TOML config file \
+        \n        --no-migrate        Opens the data directory without migrating it \
+        \n        --read-only         Never writes to the data or backup directory \
+        \n \
+        \nCOMMANDS: \
+        \n    serve                   Serves the web interface (default) \
+        \n    dump <TABLE>            Prints every entry of a table as JSON lines \
+        \n    backup create [--keep <N>] \
+        \n                            Backs up the store, keeping the newest N backups \
+        \n    backup list             Lists the backups \
+        \n    backup restore <ID>     Restores the backup with the given ID \
+        \n    backup purge --keep <N> Deletes all but the newest N backups \
+        \n    backup verify           Checks the files of every backup \
+        \n    migrate [--dry-run]     Migrates the data directory, or lists the steps \
+        \n    check-config            Validates the configuration and exits \
+        \n \
+        \nTABLES: \
+        \n    {}",
+        package(),
+        LookUp::names().join(", ")
+    );
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!("Try '{} --help' for more information.", package());
+    exit(1);
+}
+
+pub(crate) fn parse() -> Args {
+    let mut args = Args {
+        config: None,
+        no_migrate: false,
+        read_only: false,
+        command: Command::Serve,
+    };
+    let mut rest = std::env::args().skip(1);
+    let mut command = Vec::new();
+    while let Some(arg) = rest.next() {
+        if !command.is_empty() {
+            command.push(arg);
+            continue;
+        }
+        match arg.as_str() {
+            "--help" | "-h" => {
+                usage();
+                exit(0);
+            }
+            "--version" | "-V" => {
+                println!("{}", version());
+                exit(0);
+            }
+            "--config" | "-c" => {
+                let Some(path) = rest.next() else {
+                    fail("--config requires a path");
+                };
+                args.config = Some(path);
+            }
+            "--no-migrate" => args.no_migrate = true,
+            "--read-only" => args.read_only = true,
+            _ if COMMANDS.contains(&arg.as_str()) => command.push(arg),
+            _ if arg.starts_with('-') => fail(&format!("unexpected flag: {arg}")),
+            _ if args.config.is_none() => args.config = Some(arg),
+            _ => fail(&format!("unexpected argument: {arg}")),
+        }
+    }
+    if !command.is_empty() {
+        args.command = parse_command(&command).unwrap_or_else(|e| fail(&e.to_string()));
+    }
+    args
+}
+
+fn parse_command(args: &[String]) -> Result<Command> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["serve"] => Ok(Command::Serve),
+        ["dump", table] => LookUp::from_str(table)
+            .map(|table| Command::Store(StoreCommand::Dump(table)))
+            .map_err(|_| anyhow!("unknown table: {table}")),
+        ["backup", "create"] => Ok(Command::Store(StoreCommand::BackupCreate { keep: None })),
+        ["backup", "create", "--keep", n] => Ok(Command::Store(StoreCommand::BackupCreate {
+            keep: Some(n.parse().context("--keep requires a number")?),
+        })),
+        ["backup", "list"] => Ok(Command::Store(StoreCommand::BackupList)),
+        ["backup", "restore", id] => Ok(Command::Store(StoreCommand::BackupRestore(
+            id.parse().context("invalid backup ID")?,
+        ))),
+        ["backup", "purge", "--keep", n] => Ok(Command::Store(StoreCommand::BackupPurge {
+            keep: n.parse().context("--keep requires a number")?,
+        })),
+        ["backup", "verify"] => Ok(Command::Store(StoreCommand::BackupVerify)),
+        ["migrate"] => Ok(Command::Migrate { dry_run: false }),
+        ["migrate", "--dry-run"] => Ok(Command::Migrate { dry_run: true }),
+        ["check-config"] => Ok(Command::CheckConfig),
+        _ => Err(anyhow!("invalid command: {}", args.join(" "))),
+    }
+}
+
+/// Runs a command other than `serve` and prints its result to stdout.
+///
+/// # Errors
+///
+/// Returns an error if the store cannot be opened or the command fails.
+pub(crate) fn run(command: Command, config: &Config) -> Result<()> {
+    match command {
+        Command::Serve => Err(anyhow!("`serve` is not a maintenance command")),
+        Command::CheckConfig => check_config(config),
+        Command::Migrate { dry_run: true } => {
+            let status = MigrationStatus::new(State::read_version(config.data_dir())?)?;
+            print_json(&status)
+        }
+        Command::Migrate { dry_run: false } => {
+            if config.read_only() {
+                return Err(anyhow!("cannot migrate in read-only mode"));
+            }
+            State::new(config.data_dir(), config.backup_dir(), true)?;
+            println!("migrated {}", config.data_dir().display());
+            Ok(())
+        }
+        Command::Store(command) => {
+            // Only `migrate` changes the format of the data directory.
+            MigrationStatus::new(State::read_version(config.data_dir())?)?.check()?;
+            let options = OpenOptions {
+                migrate: false,
+                read_only: config.read_only(),
+                ..OpenOptions::default()
+            };
+            let state = State::open(config.data_dir(), config.backup_dir(), &options)?;
+            tokio::runtime::Runtime::new()?.block_on(run_with_state(command, &state))
+        }
+    }
+}
+
+async fn run_with_state(command: StoreCommand, state: &State) -> Result<()> {
+    match command {
+        StoreCommand::Dump(table) => print_entries(&state.entries(table).await?),
+        StoreCommand::BackupList => print_entries(&state.backups().await?),
+        StoreCommand::BackupCreate { keep } => {
+            state.create_backup(keep.unwrap_or(u32::MAX)).await?;
+            println!("backup created");
+            Ok(())
+        }
+        StoreCommand::BackupRestore(id) => {
+            state.restore_backup(id).await?;
+            println!("restored backup {id}");
+            Ok(())
+        }
+        StoreCommand::BackupPurge { keep } => {
+            state.purge_backups(keep).await?;
+            println!("kept the newest {keep} backups");
+            Ok(())
+        }
+        StoreCommand::BackupVerify => {
+            let mut failed = 0;
+            for (id, verified) in state.verify_backups().await? {
+                match verified {
+                    Ok(()) => println!("backup {id}: ok"),
+                    Err(e) => {
+                        failed += 1;
+                        println!("backup {id}: {e:#}");
+                    }
+                }
+            }
+            if failed == 0 {
+                Ok(())
+            } else {
+                Err(anyhow!("{failed} backups failed verification"))
+            }
+        }
+    }
+}
+
+fn check_config(config: &Config) -> Result<()> {
+    config.to_tls()?;
+    let version = State::read_version(config.data_dir())?;
+    println!(
+        "data directory: {} (version {version})",
+        config.data_dir().display()
+    );
+    println!("backup directory: {}", config.backup_dir().display());
+    for addr in config.listen() {
+        println!("listen: {addr}");
+    }
+    println!("configuration is valid");
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_lines<T: Serialize>(entries: &[T]) -> Result<()> {
+    for entry in entries {
+        println!("{}", serde_json::to_string(entry)?);
+    }
+    Ok(())
+}
+
+/// Prints the rows to stdout and the entries that failed to decode to
+/// stderr, failing if there are any.
+fn print_entries<T: Serialize>(entries: &Entries<T>) -> Result<()> {
+    print_lines(&entries.rows)?;
+    for failure in &entries.failures {
+        eprintln!(
+            "cannot decode entry {} (key {}): {}",
+            failure.position,
+            failure.key.as_deref().unwrap_or("unknown"),
+            failure.error
+        );
+    }
+    if entries.failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} entries could not be decoded",
+            entries.failures.len()
+        ))
+    }
+}
+
+fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+fn package() -> &'static str {
+    env!("CARGO_PKG_NAME")
+}
@@ -1,13 +1,15 @@
 mod coming;
 mod footer;
+mod forbidden;
 mod header;
 mod nav_bar;
 mod page_not_found;
 mod wrapper;
 
 pub(crate) use coming::Coming;
-use footer::Footer;
-use header::Header;
+pub(crate) use footer::Footer;
+pub(crate) use forbidden::Forbidden;
+pub(crate) use header::Header;
 use nav_bar::NavBar;
 pub(crate) use page_not_found::PageNotFound;
 pub(crate) use wrapper::Wrapper;
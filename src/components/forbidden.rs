@@ -0,0 +1,13 @@
+use dioxus::prelude::*;
+
+#[component]
+pub(crate) fn Forbidden(role: String) -> Element {
+    rsx! {
+        div {
+            class: "relative rounded-3xl bg-white shadow-xl ring-1 ring-gray-900/5",
+            style: "margin: 2%; padding: 5%;",
+            h1 { "Permission denied" }
+            p { "A {role} is not allowed to view this page." }
+        }
+    }
+}
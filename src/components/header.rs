@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 
 #[component]
-pub(crate) fn Header() -> Element {
+pub(crate) fn Header(profile: Option<String>) -> Element {
     rsx! {
         div { style: "position: sticky; top: 0;
                 background-color: white;
@@ -9,7 +9,9 @@ pub(crate) fn Header() -> Element {
                 border-bottom: 2px solid rgba(0, 0, 0, 0.5);
                 margin-left: 2px; margin-top: 8px;
                 padding-left: 2px; padding-right: 2px; padding-bottom: 2px;gap: 8px;",
-            super::NavBar {}
+            super::NavBar { profile: profile.clone() }
+            crate::server::ProfileSwitcher { profile }
+            crate::server::UserMenu {}
             a { href: "https://github.com/petabi/deview",
                 svg { style: "height: 2rem; width: 2rem;",
                     path {
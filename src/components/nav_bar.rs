@@ -2,18 +2,59 @@
 
 use dioxus::prelude::*;
 
+use crate::server::{whoami, Access, LookUp, RAW_PERMISSION};
 use crate::Route;
 
 #[component]
-pub(crate) fn NavBar() -> Element {
+pub(crate) fn NavBar(profile: Option<String>) -> Element {
+    let me = use_server_future(whoami)?;
+    let (readable, raw): (Vec<LookUp>, bool) = match (me(), &profile) {
+        (Some(Ok(me)), Some(_)) => (
+            LookUp::iter()
+                .filter(|t| t.permission().allows(me.role, Access::Read))
+                .collect(),
+            RAW_PERMISSION.allows(me.role, Access::Read),
+        ),
+        _ => (Vec::new(), false),
+    };
     rsx! {
         nav { style: "flex-grow: 1; border-right: 0.5mm solid rgba(0, 0, 0, 0.5);",
             ul {
                 li { style: "font: small-caps bold 24px sans-serif;",
-                    Link { to: Route::Home {}, "Deview" }
+                    Link { to: Route::ProfileList {}, "Deview" }
                 }
-                li {
-                    Link { to: Route::Home {}, "Home" }
+                if let Some(profile) = &profile {
+                    li {
+                        Link {
+                            to: Route::Home {
+                                profile: profile.clone(),
+                            },
+                            "Home"
+                        }
+                    }
+                }
+                for table in readable {
+                    li {
+                        Link {
+                            to: Route::Table {
+                                profile: profile.clone().unwrap_or_default(),
+                                name: table.to_string(),
+                                after: String::new(),
+                                before: String::new(),
+                            },
+                            {table.title()}
+                        }
+                    }
+                }
+                if raw {
+                    li {
+                        Link {
+                            to: Route::ColumnFamilies {
+                                profile: profile.clone().unwrap_or_default(),
+                            },
+                            "Column Families"
+                        }
+                    }
                 }
             }
         }
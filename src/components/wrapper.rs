@@ -1,9 +1,10 @@
 use dioxus::prelude::*;
 
 #[component]
-pub(crate) fn Wrapper() -> Element {
+pub(crate) fn Wrapper(profile: String) -> Element {
     rsx! {
-        super::Header {}
+        super::Header { profile: profile.clone() }
+        crate::server::ReadOnlyBanner { profile }
         Outlet::<crate::Route> {}
         super::Footer {}
     }
@@ -1,19 +1,185 @@
-use std::{env, path::PathBuf};
+use std::{env, fmt, net::SocketAddr, path::PathBuf, str::FromStr};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveTime;
 use config::{Environment, File};
 use serde::Deserialize;
 
+const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+const UNIX_PREFIX: &str = "unix:";
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     data_dir: PathBuf,
     backup_dir: PathBuf,
+    listen: Vec<Listen>,
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+    #[serde(default)]
+    ca_certs: Vec<PathBuf>,
+    /// Whether to migrate the data directory to the version this build
+    /// expects before opening it.
+    migrate: bool,
+    /// Guarantees the data and backup directories are never written to.
+    /// Implies no migration.
+    read_only: bool,
+    /// Where backups are restored for browsing and RocksDB secondary
+    /// instances keep their log files. Defaults to the system's temporary
+    /// directory.
+    scratch_dir: Option<PathBuf>,
+    /// In read-only mode, how often to catch up with the data directory, in
+    /// seconds. Use it to follow a REview that is still running on
+    /// `data_dir`.
+    refresh_interval: Option<u64>,
+    /// More data directories that can be opened from the UI, next to
+    /// `data_dir` and `backup_dir`, which form the default profile.
+    #[serde(default)]
+    profiles: Vec<Profile>,
+    /// Backs up the default profile periodically while serving.
+    backup_schedule: Option<BackupSchedule>,
+    /// The bearer token `/metrics` requires; without one, no metrics are
+    /// served.
+    metrics_token: Option<String>,
+    #[serde(default)]
+    log: crate::logging::Log,
+}
+
+/// How often Deview backs up the store on its own.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BackupSchedule {
+    /// Seconds between backups.
+    pub interval: u64,
+    /// How many backups to keep; older ones are purged after each backup.
+    pub keep: u32,
+    /// The time of day backups may start, so they stay out of busy hours.
+    pub window: Option<TimeWindow>,
+}
+
+/// A daily range of time in UTC, written as `01:00-05:00`. A window that ends
+/// before it starts spans midnight.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow!("invalid time window \"{s}\": use HH:MM-HH:MM"))?;
+        let parse = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .with_context(|| format!("invalid time \"{t}\" in time window \"{s}\""))
+        };
+        let window = Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        if window.start == window.end {
+            return Err(anyhow!("empty time window \"{s}\""));
+        }
+        Ok(window)
+    }
+}
+
+impl TryFrom<String> for TimeWindow {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// A named pair of data and backup directories.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub data_dir: PathBuf,
+    pub backup_dir: PathBuf,
+    /// Whether to migrate the data directory when the profile is opened.
+    /// Off by default, because any signed-in user's visit opens it; an
+    /// administrator can migrate it from its home page instead.
+    #[serde(default)]
+    pub migrate: bool,
+}
+
+/// An address Deview accepts connections on.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum Listen {
+    /// A TCP address and port, e.g. `127.0.0.1:8080`.
+    Tcp(SocketAddr),
+    /// A Unix domain socket, written as `unix:/path/to/deview.sock`.
+    Unix(PathBuf),
+}
+
+impl FromStr for Listen {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(path) = s.strip_prefix(UNIX_PREFIX) {
+            if path.is_empty() {
+                return Err(anyhow!("empty Unix socket path in \"{s}\""));
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        s.parse()
+            .map(Self::Tcp)
+            .with_context(|| format!("invalid listen address \"{s}\""))
+    }
+}
+
+impl TryFrom<String> for Listen {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Listen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "{UNIX_PREFIX}{}", path.display()),
+        }
+    }
 }
 
 impl Config {
     /// Reads configuration from the file on disk and environment variables and
     /// returns Config struct.
     ///
+    /// `listen`, `ca_certs` and `log.filters` take lists; in the environment
+    /// their items are separated by commas, e.g.
+    /// `REVIEW_LISTEN=0.0.0.0:8080,unix:/tmp/deview.sock`. Nested keys are
+    /// joined with two underscores, e.g. `REVIEW_LOG__LEVEL=debug`.
+    ///
     /// # Errors
     ///
     /// If input arguments are invalid, an error will be returned.
@@ -22,21 +188,238 @@ impl Config {
             .set_default("data_dir", env::current_dir()?.join("data").to_str())
             .context("cannot set the default data directory")?
             .set_default("backup_dir", env::current_dir()?.join("backup").to_str())
-            .context("cannot set the default backup directory")?;
+            .context("cannot set the default backup directory")?
+            .set_default("listen", vec![DEFAULT_LISTEN])
+            .context("cannot set the default listen address")?
+            .set_default("migrate", true)
+            .context("cannot set the default migration setting")?
+            .set_default("read_only", false)
+            .context("cannot set the default read-only setting")?;
         let config: Config = if let Some(path) = path {
             builder.add_source(File::with_name(path))
         } else {
             builder
         }
-        .add_source(Environment::with_prefix("REVIEW"))
+        .add_source(
+            Environment::with_prefix("REVIEW")
+                .prefix_separator("_")
+                .separator("__")
+                .try_parsing(true)
+                .list_separator(",")
+                .with_list_parse_key("listen")
+                .with_list_parse_key("ca_certs")
+                .with_list_parse_key("log.filters"),
+        )
         .build()
         .context("cannot build the config")?
         .try_deserialize()?;
+        if config.listen.is_empty() {
+            return Err(anyhow!("at least one listen address is required"));
+        }
+        if config.cert.is_some() != config.key.is_some() {
+            return Err(anyhow!("`cert` and `key` must be set together"));
+        }
+        if config.refresh_interval.is_some() && !config.read_only {
+            return Err(anyhow!("`refresh_interval` requires `read_only`"));
+        }
+        if config.refresh_interval == Some(0) {
+            return Err(anyhow!("`refresh_interval` must be positive"));
+        }
+        if let Some(schedule) = &config.backup_schedule {
+            if config.read_only {
+                return Err(anyhow!("`backup_schedule` cannot be used with `read_only`"));
+            }
+            if schedule.interval == 0 {
+                return Err(anyhow!("`backup_schedule.interval` must be positive"));
+            }
+            if schedule.keep == 0 {
+                return Err(anyhow!("`backup_schedule.keep` must be positive"));
+            }
+        }
+        if config.cert.is_none() && !config.ca_certs.is_empty() {
+            return Err(anyhow!("`ca_certs` requires `cert` and `key`"));
+        }
+        config.log.validate()?;
+        let mut names = vec![crate::server::DEFAULT_PROFILE];
+        for profile in &config.profiles {
+            if !is_valid_profile_name(&profile.name) {
+                return Err(anyhow!(
+                    "invalid profile name \"{}\": use letters, digits, '-' and '_'",
+                    profile.name
+                ));
+            }
+            if names.contains(&profile.name.as_str()) {
+                return Err(anyhow!("duplicate profile name \"{}\"", profile.name));
+            }
+            names.push(&profile.name);
+        }
         Ok(config)
     }
 
+    /// Opens the data directory as is, even if it is older than this build
+    /// expects.
+    pub fn skip_migration(&mut self) {
+        self.migrate = false;
+    }
+
+    pub fn set_read_only(&mut self) {
+        self.read_only = true;
+    }
+
+    pub fn refresh_interval(&self) -> Option<std::time::Duration> {
+        self.refresh_interval
+            .filter(|_| self.read_only)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// Returns the backup schedule, unless the store must not be written to.
+    pub fn backup_schedule(&self) -> Option<&BackupSchedule> {
+        self.backup_schedule.as_ref().filter(|_| !self.read_only)
+    }
+
+    pub fn metrics_token(&self) -> Option<&str> {
+        self.metrics_token.as_deref()
+    }
+
+    pub fn data_dir(&self) -> &std::path::Path {
+        &self.data_dir
+    }
+
+    pub fn backup_dir(&self) -> &std::path::Path {
+        &self.backup_dir
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn log(&self) -> &crate::logging::Log {
+        &self.log
+    }
+
+    pub fn listen(&self) -> &[Listen] {
+        &self.listen
+    }
+
+    /// Loads the TLS certificate if `cert` and `key` are set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the certificate, key or CA files cannot be read.
+    pub fn to_tls(&self) -> Result<Option<crate::tls::Tls>> {
+        match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => crate::tls::Tls::new(cert, key, &self.ca_certs).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn open_options(&self) -> crate::server::OpenOptions {
+        crate::server::OpenOptions {
+            migrate: self.migrate,
+            read_only: self.read_only,
+            scratch: self.scratch_dir.clone(),
+        }
+    }
+
+    /// Opens the default profile.
     #[cfg(feature = "server")]
     pub fn to_state(&self) -> Result<crate::server::State> {
-        crate::server::State::new(&self.data_dir, &self.backup_dir)
+        crate::server::State::open(&self.data_dir, &self.backup_dir, &self.open_options())
+    }
+
+    /// Opens the default profile and prepares the others to be opened on
+    /// demand.
+    #[cfg(feature = "server")]
+    pub fn to_profiles(&self) -> Result<crate::server::Profiles> {
+        Ok(crate::server::Profiles::new(
+            self.to_state()?,
+            self.profiles.clone(),
+            self.open_options(),
+            self.refresh_interval(),
+        ))
+    }
+}
+
+/// Whether `name` can be used in a URL as is.
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::NaiveTime;
+
+    use super::{Listen, TimeWindow};
+
+    fn time(t: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(t, "%H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn time_window() {
+        let window: TimeWindow = "01:00-05:00".parse().unwrap();
+        assert_eq!(window.start, time("01:00:00"));
+        assert_eq!(window.end, time("05:00:00"));
+        assert_eq!(window.to_string(), "01:00-05:00");
+        assert!(!window.contains(time("00:59:59")));
+        assert!(window.contains(time("01:00:00")));
+        assert!(window.contains(time("04:59:59")));
+        assert!(!window.contains(time("05:00:00")));
+        assert!(!window.contains(time("12:00:00")));
+    }
+
+    #[test]
+    fn time_window_across_midnight() {
+        let window: TimeWindow = " 22:00 - 02:00 ".parse().unwrap();
+        assert_eq!(window.to_string(), "22:00-02:00");
+        assert!(!window.contains(time("21:59:59")));
+        assert!(window.contains(time("22:00:00")));
+        assert!(window.contains(time("23:59:59")));
+        assert!(window.contains(time("00:00:00")));
+        assert!(window.contains(time("01:59:59")));
+        assert!(!window.contains(time("02:00:00")));
+        assert!(!window.contains(time("12:00:00")));
+    }
+
+    #[test]
+    fn time_window_invalid() {
+        assert!("01:00".parse::<TimeWindow>().is_err());
+        assert!("01:00-25:00".parse::<TimeWindow>().is_err());
+        assert!("1am-5am".parse::<TimeWindow>().is_err());
+        assert!("03:00-03:00".parse::<TimeWindow>().is_err());
+        assert!("".parse::<TimeWindow>().is_err());
+    }
+
+    #[test]
+    fn listen_tcp() {
+        let listen: Listen = "127.0.0.1:8080".parse().unwrap();
+        assert_eq!(listen, Listen::Tcp("127.0.0.1:8080".parse().unwrap()));
+        assert_eq!(listen.to_string(), "127.0.0.1:8080");
+
+        let listen: Listen = "[::1]:443".parse().unwrap();
+        assert_eq!(listen, Listen::Tcp("[::1]:443".parse().unwrap()));
+        assert_eq!(listen.to_string(), "[::1]:443");
+    }
+
+    #[test]
+    fn listen_unix() {
+        let listen: Listen = "unix:/run/deview.sock".parse().unwrap();
+        assert_eq!(listen, Listen::Unix(PathBuf::from("/run/deview.sock")));
+        assert_eq!(listen.to_string(), "unix:/run/deview.sock");
+    }
+
+    #[test]
+    fn listen_invalid() {
+        assert!("unix:".parse::<Listen>().is_err());
+        assert!("localhost".parse::<Listen>().is_err());
+        assert!("127.0.0.1".parse::<Listen>().is_err());
+        assert!("127.0.0.1:http".parse::<Listen>().is_err());
+        assert!("".parse::<Listen>().is_err());
     }
 }
@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{self, RollingFileAppender},
+};
+use tracing_subscriber::{filter::LevelFilter, fmt::writer::BoxMakeWriter, EnvFilter};
+
+/// Where and how Deview logs.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Log {
+    /// The most verbose level logged by default, e.g. "info" or "debug".
+    level: String,
+    /// Directives for individual modules, e.g. "review_database=warn" or
+    /// "deview::access=off".
+    filters: Vec<String>,
+    format: Format,
+    /// Writes to this file instead of stderr. Rotated files are named after
+    /// it with the date appended.
+    file: Option<PathBuf>,
+    rotation: Rotation,
+    /// How many rotated files to keep; all of them if unset.
+    max_files: Option<usize>,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            filters: Vec::new(),
+            format: Format::Text,
+            file: None,
+            rotation: Rotation::Daily,
+            max_files: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Rotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl From<Rotation> for rolling::Rotation {
+    fn from(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::Minutely => Self::MINUTELY,
+            Rotation::Hourly => Self::HOURLY,
+            Rotation::Daily => Self::DAILY,
+            Rotation::Never => Self::NEVER,
+        }
+    }
+}
+
+impl Log {
+    /// Checks the level and filters without installing a subscriber.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the level or a filter cannot be parsed.
+    pub fn validate(&self) -> Result<()> {
+        self.env_filter().map(|_| ())
+    }
+
+    fn env_filter(&self) -> Result<EnvFilter> {
+        let level: LevelFilter = self
+            .level
+            .parse()
+            .with_context(|| format!("invalid log level \"{}\"", self.level))?;
+        let mut filter = EnvFilter::builder()
+            .with_default_directive(level.into())
+            .parse("")?;
+        for directive in &self.filters {
+            filter = filter.add_directive(
+                directive
+                    .parse()
+                    .with_context(|| format!("invalid log filter \"{directive}\""))?,
+            );
+        }
+        Ok(filter)
+    }
+
+    fn writer(&self) -> Result<(BoxMakeWriter, Option<WorkerGuard>)> {
+        let Some(file) = &self.file else {
+            return Ok((BoxMakeWriter::new(std::io::stderr), None));
+        };
+        let prefix = file
+            .file_name()
+            .ok_or_else(|| anyhow!("invalid log file {}", file.display()))?;
+        let dir = file.parent().unwrap_or_else(|| Path::new("."));
+        let mut builder = RollingFileAppender::builder()
+            .rotation(self.rotation.into())
+            .filename_prefix(prefix.to_string_lossy());
+        if let Some(max_files) = self.max_files {
+            builder = builder.max_log_files(max_files);
+        }
+        let appender = builder
+            .build(dir)
+            .with_context(|| format!("cannot open log file {}", file.display()))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        Ok((BoxMakeWriter::new(writer), Some(guard)))
+    }
+
+    /// Installs the global logger. Keep the returned guard until the process
+    /// exits; dropping it flushes the log file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings are invalid, the log file cannot be
+    /// opened, or a logger is already installed.
+    pub fn init(&self) -> Result<Option<WorkerGuard>> {
+        let (writer, guard) = self.writer()?;
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(self.env_filter()?)
+            .with_writer(writer)
+            .with_ansi(self.file.is_none());
+        match self.format {
+            Format::Text => builder.try_init(),
+            Format::Json => builder.json().try_init(),
+        }
+        .map_err(|e| anyhow!(e))?;
+        Ok(guard)
+    }
+}
@@ -1,24 +1,42 @@
 #![allow(non_snake_case)]
 
+#[cfg(feature = "server")]
+mod cli;
 mod components;
 #[cfg(feature = "server")]
 mod config;
+#[cfg(feature = "server")]
+mod logging;
+#[cfg(feature = "server")]
+mod serve;
 mod server;
+#[cfg(feature = "server")]
+mod tls;
 
 use dioxus::prelude::*;
 
 use crate::components::PageNotFound;
-use crate::server::Table;
+use crate::server::{ColumnFamilies, ColumnFamily, Login, ProfileList, Table};
 
 #[derive(Clone, Routable, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 #[rustfmt::skip]
 enum Route {
-    #[layout(components::Wrapper)]
-        #[route("/")]
-        Home {},
-        #[route("/table/:name")]
-        Table { name: String },
-    #[end_layout]
+    #[route("/")]
+    ProfileList {},
+    #[nest("/p/:profile")]
+        #[layout(components::Wrapper)]
+            #[route("/")]
+            Home { profile: String },
+            #[route("/table/:name?:after&:before")]
+            Table { profile: String, name: String, after: String, before: String },
+            #[route("/raw")]
+            ColumnFamilies { profile: String },
+            #[route("/raw/:name?:after&:before")]
+            ColumnFamily { profile: String, name: String, after: String, before: String },
+        #[end_layout]
+    #[end_nest]
+    #[route("/login")]
+    Login {},
     #[route("/:..route")]
     PageNotFound { route: Vec<String> },
 }
@@ -32,51 +50,113 @@ fn main() {
 
     #[cfg(feature = "server")]
     {
-        use axum::Extension;
-        use axum::Router;
         use dioxus_logger::tracing;
 
-        if let Err(e) = dioxus_logger::init(tracing::Level::INFO) {
-            println!("failed to initiate logger {e:?}");
-            std::process::exit(1);
-        }
-        tracing::info!("starting app");
-
-        let config = match config::Config::load_config(parse().as_deref()) {
+        let args = cli::parse();
+        let mut config = match config::Config::load_config(args.config.as_deref()) {
             Ok(c) => c,
             Err(e) => {
-                tracing::error!("failed to load config: {e}");
+                eprintln!("failed to load config: {e:#}");
                 std::process::exit(1);
             }
         };
-
-        let review = match config.to_state() {
-            Ok(state) => state,
+        let log_guard = match config.log().init() {
+            Ok(guard) => guard,
             Err(e) => {
-                tracing::error!("failed to load state: {}", e);
+                eprintln!("failed to initiate logger: {e:#}");
                 std::process::exit(1);
             }
         };
-        tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async move {
-                let app = Router::new()
-                    // Server side render the application, serve static assets, and register server functions
-                    .serve_dioxus_application(ServeConfig::builder().build(), move || {
-                        VirtualDom::new(App)
-                    })
-                    .await
-                    .layer(Extension(review));
-                let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
-                let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+        if args.no_migrate {
+            config.skip_migration();
+        }
+        if args.read_only {
+            config.set_read_only();
+        }
 
-                axum::serve(listener, app.into_make_service())
-                    .await
-                    .unwrap();
-            });
+        match args.command {
+            cli::Command::Serve => serve(config),
+            command => {
+                if let Err(e) = cli::run(command, &config) {
+                    tracing::error!("{e:#}");
+                    drop(log_guard);
+                    std::process::exit(1);
+                }
+            }
+        }
     }
 }
 
+#[cfg(feature = "server")]
+fn serve(config: config::Config) {
+    use axum::routing::get;
+    use axum::Extension;
+    use axum::Router;
+    use dioxus_logger::tracing;
+
+    tracing::info!("starting app");
+    let profiles = match config.to_profiles() {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            tracing::error!("failed to load state: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let tls = match config.to_tls() {
+        Ok(tls) => tls,
+        Err(e) => {
+            tracing::error!("failed to load TLS certificate: {e:#}");
+            std::process::exit(1);
+        }
+    };
+    let sessions = server::Sessions::new(tls.is_some());
+    tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(async move {
+            if let Some(interval) = config.refresh_interval() {
+                tokio::spawn(profiles.default_state().clone().catch_up_every(interval));
+            }
+            if let Some(schedule) = config.backup_schedule() {
+                tokio::spawn(
+                    profiles
+                        .default_state()
+                        .clone()
+                        .back_up_on(schedule.clone()),
+                );
+            }
+            let app = Router::new()
+                .route("/healthz", get(server::healthz))
+                .route("/readyz", get(server::readyz))
+                .route("/metrics", get(server::metrics))
+                // Server side render the application, serve static assets, and register server functions
+                .serve_dioxus_application(ServeConfig::builder().build(), move || {
+                    VirtualDom::new(App)
+                })
+                .await
+                // Layers added later run first, so only calls that passed the
+                // sign-in check are tracked.
+                .layer(axum::middleware::from_fn(server::track_server_fns))
+                .layer(axum::middleware::from_fn(server::require_login))
+                .layer(axum::middleware::from_fn(server::access_log))
+                .layer(Extension(sessions))
+                .layer(Extension(server::MetricsToken(
+                    config.metrics_token().map(str::to_string),
+                )))
+                .layer(Extension(profiles.clone()));
+
+            let began =
+                match serve::serve(config.listen(), tls, app, serve::shutdown_signal()).await {
+                    Ok(began) => began,
+                    Err(e) => {
+                        tracing::error!("failed to serve: {e:#}");
+                        std::process::exit(1);
+                    }
+                };
+            profiles.close_all().await;
+            tracing::info!("shut down in {:.2?}", began.elapsed());
+        });
+}
+
 #[cfg(any(feature = "server", feature = "web"))]
 fn App() -> Element {
     let asset = format!("{}/assets/tailwind.css", env!("CARGO_MANIFEST_DIR"));
@@ -89,57 +169,15 @@ fn App() -> Element {
 }
 
 #[component]
-fn Home() -> Element {
+fn Home(profile: String) -> Element {
     rsx! {
         div { class: "flex min-h-screen flex-col justify-center overflow-scroll",
             div {
                 class: "rounded-3xl bg-white shadow-xl ring-1 ring-gray-900/5",
                 style: "margin: 2%; padding: 5%; overflow: scroll;",
-                server::TableDigest {}
+                server::Migration { profile: profile.clone() }
+                server::TableDigest { profile }
             }
         }
     }
 }
-
-#[cfg(feature = "server")]
-fn parse() -> Option<String> {
-    use std::process::exit;
-    let args = std::env::args().collect::<Vec<_>>();
-    if args.len() <= 1 {
-        return None;
-    }
-
-    if args[1] == "--help" || args[1] == "-h" {
-        println!("{} {}", package(), version());
-        println!();
-        println!(
-            "USAGE: \
-            \n    {} [CONFIG] \
-            \n \
-            \nFLAGS: \
-            \n    -h, --help       Prints help information \
-            \n    -V, --version    Prints version information \
-            \n \
-            \nARG: \
-            \n    <CONFIG>    A TOML config file",
-            package()
-        );
-        exit(0);
-    }
-    if args[1] == "--version" || args[1] == "-V" {
-        println!("{}", version());
-        exit(0);
-    }
-
-    Some(args[1].clone())
-}
-
-#[cfg(feature = "server")]
-fn version() -> &'static str {
-    env!("CARGO_PKG_VERSION")
-}
-
-#[cfg(feature = "server")]
-fn package() -> &'static str {
-    env!("CARGO_PKG_NAME")
-}
@@ -0,0 +1,224 @@
+use std::{
+    future::Future,
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use axum::Router;
+use dioxus_logger::tracing;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, UnixListener},
+    sync::watch,
+    task::JoinSet,
+};
+
+use crate::{config::Listen, tls::Tls};
+
+/// How long to wait for open connections to finish their requests after a
+/// shutdown signal.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to pause after failing to accept a connection, so that running
+/// out of file descriptors does not turn the accept loop into a busy loop.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Binds every address in `listen` and serves `app` on all of them until
+/// `shutdown` completes, then stops accepting connections and waits for the
+/// open ones to finish their requests, closing those still open after
+/// [`DRAIN_TIMEOUT`]. Returns when the shutdown began.
+///
+/// When `tls` is given, TCP listeners speak HTTPS; Unix domain sockets always
+/// serve plain HTTP, as they are meant for a local reverse proxy.
+///
+/// # Errors
+///
+/// Returns an error if any of the addresses cannot be bound.
+pub(crate) async fn serve(
+    listen: &[Listen],
+    tls: Option<Tls>,
+    app: Router,
+    shutdown: impl Future<Output = ()>,
+) -> Result<Instant> {
+    if let Some(tls) = &tls {
+        tokio::spawn(tls.clone().reload_on_hangup());
+    }
+
+    // Every listener and connection holds a receiver, which learns of the
+    // shutdown when the sender sends.
+    let (stop, stopped) = watch::channel(());
+    let mut listeners = JoinSet::new();
+    for addr in listen {
+        match addr {
+            Listen::Tcp(sock) => {
+                let listener = TcpListener::bind(sock)
+                    .await
+                    .with_context(|| format!("cannot listen on {addr}"))?;
+                if tls.is_some() {
+                    tracing::info!("listening on {addr} (HTTPS)");
+                } else {
+                    tracing::info!("listening on {addr}");
+                }
+                listeners.spawn(accept_loop(
+                    listener,
+                    tls.clone(),
+                    app.clone(),
+                    stopped.clone(),
+                ));
+            }
+            Listen::Unix(path) => {
+                let listener =
+                    bind_unix(path).with_context(|| format!("cannot listen on {addr}"))?;
+                tracing::info!("listening on {addr}");
+                listeners.spawn(accept_loop(listener, None, app.clone(), stopped.clone()));
+            }
+        }
+    }
+
+    drop(stopped);
+
+    shutdown.await;
+    let began = Instant::now();
+    tracing::info!("shutting down, waiting for open connections to finish");
+    let _ = stop.send(());
+    let mut connections = Vec::with_capacity(listeners.len());
+    while let Some(res) = listeners.join_next().await {
+        connections.push(res.context("listener task panicked")?);
+    }
+    let drain = async {
+        for set in &mut connections {
+            while set.join_next().await.is_some() {}
+        }
+    };
+    if tokio::time::timeout(DRAIN_TIMEOUT, drain).await.is_err() {
+        let open: usize = connections.iter().map(JoinSet::len).sum();
+        tracing::warn!("closing {open} connections that did not finish in time");
+        for set in &mut connections {
+            set.shutdown().await;
+        }
+    }
+    Ok(began)
+}
+
+/// Completes when the process receives SIGINT or SIGTERM.
+pub(crate) async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!("cannot watch SIGTERM: {e}");
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => tracing::info!("received SIGINT"),
+        _ = terminate.recv() => tracing::info!("received SIGTERM"),
+    }
+}
+
+/// Binds a Unix domain socket, replacing a stale socket file left behind by a
+/// previous run.
+fn bind_unix(path: &Path) -> io::Result<UnixListener> {
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if meta.file_type().is_socket() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    UnixListener::bind(path)
+}
+
+trait Accept: Send + 'static {
+    type Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    fn accept(&self) -> impl Future<Output = io::Result<Self::Stream>> + Send;
+}
+
+impl Accept for TcpListener {
+    type Stream = tokio::net::TcpStream;
+
+    async fn accept(&self) -> io::Result<Self::Stream> {
+        TcpListener::accept(self).await.map(|(stream, _)| stream)
+    }
+}
+
+impl Accept for UnixListener {
+    type Stream = tokio::net::UnixStream;
+
+    async fn accept(&self) -> io::Result<Self::Stream> {
+        UnixListener::accept(self).await.map(|(stream, _)| stream)
+    }
+}
+
+/// Accepts connections until a shutdown is signaled, and returns the tasks
+/// of the connections still open then.
+async fn accept_loop<L: Accept>(
+    listener: L,
+    tls: Option<Tls>,
+    app: Router,
+    mut stopped: watch::Receiver<()>,
+) -> JoinSet<()> {
+    let mut connections = JoinSet::new();
+    loop {
+        let stream = tokio::select! {
+            res = listener.accept() => match res {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::warn!("failed to accept a connection: {e}");
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            },
+            // Reaps finished connections so the set does not keep growing.
+            Some(_) = connections.join_next() => continue,
+            _ = stopped.changed() => return connections,
+        };
+        let service = TowerToHyperService::new(app.clone());
+        let acceptor = tls.as_ref().map(Tls::acceptor);
+        let stopped = stopped.clone();
+        connections.spawn(async move {
+            match acceptor {
+                Some(acceptor) => match acceptor.accept(stream).await {
+                    Ok(stream) => serve_connection(stream, service, stopped).await,
+                    Err(e) => tracing::debug!("TLS handshake failed: {e}"),
+                },
+                None => serve_connection(stream, service, stopped).await,
+            }
+        });
+    }
+}
+
+/// Serves requests on one connection until the client closes it or a
+/// shutdown is signaled, in which case the request in progress is completed
+/// first.
+async fn serve_connection<S>(
+    stream: S,
+    service: TowerToHyperService<Router>,
+    mut stopped: watch::Receiver<()>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let builder = Builder::new(TokioExecutor::new());
+    let conn = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+    tokio::pin!(conn);
+    let res = tokio::select! {
+        res = conn.as_mut() => res,
+        _ = stopped.changed() => {
+            conn.as_mut().graceful_shutdown();
+            conn.await
+        }
+    };
+    if let Err(e) = res {
+        tracing::debug!("connection closed with an error: {e}");
+    }
+}
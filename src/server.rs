@@ -1,7 +1,43 @@
+#[cfg(feature = "server")]
+mod access_log;
+mod auth;
+#[cfg(feature = "server")]
+mod health;
+#[cfg(feature = "server")]
+mod metrics;
+mod migration;
+mod permission;
+mod profile;
+mod raw;
+#[cfg(feature = "server")]
+mod rocks;
+#[cfg(feature = "server")]
+mod schedule;
+#[cfg(feature = "server")]
+mod snapshot;
 mod state;
 mod tables;
 
 #[cfg(feature = "server")]
-pub(crate) use self::state::State;
-pub(crate) use self::state::{BackupDigest, Digest as Info};
-pub(crate) use self::tables::{Digest as TableDigest, Table};
+pub(crate) use self::access_log::access_log;
+#[cfg(feature = "server")]
+pub(crate) use self::auth::{require_login, Sessions};
+pub(crate) use self::auth::{whoami, Login, UserMenu};
+#[cfg(feature = "server")]
+pub(crate) use self::health::{healthz, readyz};
+#[cfg(feature = "server")]
+pub(crate) use self::metrics::{metrics, track_server_fns, MetricsToken};
+pub(crate) use self::migration::Migration;
+#[cfg(feature = "server")]
+pub(crate) use self::migration::MigrationStatus;
+pub(crate) use self::permission::Access;
+#[cfg(feature = "server")]
+pub(crate) use self::profile::Profiles;
+pub(crate) use self::profile::{ProfileList, ProfileSwitcher, DEFAULT_PROFILE};
+pub(crate) use self::raw::{ColumnFamilies, ColumnFamily, PERMISSION as RAW_PERMISSION};
+pub(crate) use self::state::{Digest as Info, ReadOnlyBanner};
+#[cfg(feature = "server")]
+pub(crate) use self::state::{OpenOptions, State};
+#[cfg(feature = "server")]
+pub(crate) use self::tables::Entries;
+pub(crate) use self::tables::{Digest as TableDigest, LookUp, Table};
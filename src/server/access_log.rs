@@ -0,0 +1,28 @@
+use std::time::Instant;
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use dioxus_logger::tracing;
+
+use super::auth::User;
+
+/// Logs every request with the `deview::access` target, so a log filter can
+/// send it elsewhere or turn it off.
+pub async fn access_log(req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_owned();
+    let began = Instant::now();
+    let response = next.run(req).await;
+    let user = response
+        .extensions()
+        .get::<User>()
+        .map_or("-", |user| user.username.as_str());
+    tracing::info!(
+        target: "deview::access",
+        %method,
+        path,
+        status = response.status().as_u16(),
+        latency_ms = began.elapsed().as_secs_f64() * 1000.0,
+        user,
+    );
+    response
+}
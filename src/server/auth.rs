@@ -0,0 +1,461 @@
+#[cfg(feature = "server")]
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "server")]
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+    Extension,
+};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+
+use super::permission::Role;
+#[cfg(feature = "server")]
+use super::permission::{Access, Permission};
+#[cfg(feature = "server")]
+use crate::server::{Profiles, State};
+
+#[cfg(feature = "server")]
+const SESSION_COOKIE: &str = "deview_session";
+#[cfg(feature = "server")]
+const SESSION_TTL: Duration = Duration::from_secs(8 * 60 * 60);
+
+/// The account a request is made on behalf of.
+#[cfg(feature = "server")]
+#[derive(Clone, Debug)]
+pub(crate) struct User {
+    pub(crate) username: String,
+    pub(crate) role: Role,
+}
+
+/// A signed-in browser. Only the username is kept; the role is read from the
+/// account on every request so that changing or deleting an account takes
+/// effect at once.
+#[cfg(feature = "server")]
+struct Session {
+    username: String,
+    expires_at: Instant,
+}
+
+/// Signed-in browser sessions, keyed by the value of the session cookie.
+#[cfg(feature = "server")]
+#[derive(Clone)]
+pub struct Sessions {
+    inner: Arc<RwLock<HashMap<String, Session>>>,
+    /// Whether session cookies are only sent over HTTPS.
+    secure: bool,
+}
+
+#[cfg(feature = "server")]
+impl Sessions {
+    /// Creates an empty set of sessions. `secure` marks the session cookie
+    /// `Secure`; set it when Deview serves HTTPS.
+    pub fn new(secure: bool) -> Self {
+        Self {
+            inner: Arc::default(),
+            secure,
+        }
+    }
+
+    /// Returns the `Set-Cookie` value that stores `value` in the session
+    /// cookie, with `attributes` appended.
+    fn cookie(&self, value: &str, attributes: &str) -> String {
+        let secure = if self.secure { "; Secure" } else { "" };
+        format!("{SESSION_COOKIE}={value}; Path=/; HttpOnly{secure}; {attributes}")
+    }
+
+    async fn open(&self, username: String) -> String {
+        let id = session_id();
+        let now = Instant::now();
+        let mut sessions = self.inner.write().await;
+        sessions.retain(|_, s| s.expires_at > now);
+        sessions.insert(
+            id.clone(),
+            Session {
+                username,
+                expires_at: now + SESSION_TTL,
+            },
+        );
+        id
+    }
+
+    /// Returns the username of a live session and extends its lifetime.
+    async fn touch(&self, id: &str) -> Option<String> {
+        let now = Instant::now();
+        let mut sessions = self.inner.write().await;
+        match sessions.get_mut(id) {
+            Some(session) if session.expires_at > now => {
+                session.expires_at = now + SESSION_TTL;
+                Some(session.username.clone())
+            }
+            Some(_) => {
+                sessions.remove(id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn close(&self, id: &str) {
+        self.inner.write().await.remove(id);
+    }
+}
+
+#[cfg(feature = "server")]
+fn session_id() -> String {
+    use rand::RngCore;
+
+    let mut bytes = [0_u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(feature = "server")]
+fn session_cookie(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == SESSION_COOKIE).then_some(value)
+        })
+}
+
+/// The files in the asset directory, which are served from the root.
+#[cfg(feature = "server")]
+const PUBLIC_FILES: &[&str] = &["/favicon.ico", "/header.svg", "/main.css", "/tailwind.css"];
+
+/// The directories the client bundle is served from.
+#[cfg(feature = "server")]
+const PUBLIC_DIRS: &[&str] = &["/assets/", "/wasm/"];
+
+/// Whether `path` can be requested without signing in: the login page, the
+/// login server function, the health checks, the metrics, which check their
+/// own token, and static assets.
+#[cfg(feature = "server")]
+fn is_public(path: &str) -> bool {
+    matches!(
+        path,
+        "/login" | "/api/login" | "/healthz" | "/readyz" | "/metrics"
+    ) || PUBLIC_FILES.contains(&path)
+        || PUBLIC_DIRS
+            .iter()
+            .any(|dir| path.starts_with(dir) && !path.contains(".."))
+}
+
+/// Rejects requests without a live session or a valid access token. Pages
+/// redirect to the login page and server functions answer with 401
+/// Unauthorized.
+///
+/// Scripts authenticate with `Authorization: Bearer <token>`, where the token
+/// is one REview issued and still has in the access token table of the
+/// default profile.
+#[cfg(feature = "server")]
+pub async fn require_login(
+    Extension(profiles): Extension<Profiles>,
+    Extension(sessions): Extension<Sessions>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path().to_owned();
+    if is_public(&path) {
+        return next.run(req).await;
+    }
+    let review = profiles.default_state();
+    let mut username = match session_cookie(req.headers()) {
+        Some(id) => sessions.touch(id).await,
+        None => None,
+    };
+    // A stale session cookie must not hide a valid access token.
+    if username.is_none() {
+        if let Some(token) = bearer_token(req.headers()) {
+            username = match token_username(review, token).await {
+                Ok(username) => username,
+                Err(e) => {
+                    dioxus_logger::tracing::error!("cannot validate access token: {e:#}");
+                    None
+                }
+            };
+        }
+    }
+    let user = match username {
+        Some(username) => match account_user(review, username).await {
+            Ok(user) => user,
+            Err(e) => {
+                dioxus_logger::tracing::error!("cannot look up the signed-in account: {e:#}");
+                None
+            }
+        },
+        None => None,
+    };
+    match user {
+        Some(user) => {
+            req.extensions_mut().insert(user.clone());
+            let mut response = next.run(req).await;
+            // Lets the access log name the user.
+            response.extensions_mut().insert(user);
+            response
+        }
+        None if path.starts_with("/api/") => StatusCode::UNAUTHORIZED.into_response(),
+        None => Redirect::to("/login").into_response(),
+    }
+}
+
+#[cfg(feature = "server")]
+pub(super) fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Looks up the username an access token was issued to: the account named
+/// by the token's `sub` claim, if the token is one of its tokens. Tokens
+/// whose `exp` claim has passed are rejected even if REview has not removed
+/// them yet.
+#[cfg(feature = "server")]
+async fn token_username(review: &State, token: &str) -> anyhow::Result<Option<String>> {
+    let Some(claims) = jwt_claims(token) else {
+        return Ok(None);
+    };
+    if claims
+        .get("exp")
+        .and_then(serde_json::Value::as_i64)
+        .is_some_and(|exp| exp <= chrono::Utc::now().timestamp())
+    {
+        return Ok(None);
+    }
+    let Some(username) = claims.get("sub").and_then(serde_json::Value::as_str) else {
+        return Ok(None);
+    };
+    let found = review.has_token(username, token).await?;
+    Ok(found.then(|| username.to_string()))
+}
+
+/// Compares `a` and `b` in time that depends only on their lengths.
+#[cfg(feature = "server")]
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Returns `username` with the role its account has now, or `None` if the
+/// account no longer exists.
+#[cfg(feature = "server")]
+async fn account_user(review: &State, username: String) -> anyhow::Result<Option<User>> {
+    let account = review.find_account(&username).await?;
+    let role = account.map(|a| a.role.into());
+    Ok(role.map(|role| User { username, role }))
+}
+
+/// Returns the claims of a JWT without verifying its signature.
+#[cfg(feature = "server")]
+fn jwt_claims(token: &str) -> Option<serde_json::Value> {
+    use data_encoding::BASE64URL_NOPAD;
+
+    let payload = token.split('.').nth(1)?;
+    let payload = BASE64URL_NOPAD.decode(payload.as_bytes()).ok()?;
+    serde_json::from_slice(&payload).ok()
+}
+
+/// Returns the `exp` claim of a JWT without verifying its signature.
+#[cfg(feature = "server")]
+pub(crate) fn jwt_expiry(token: &str) -> Option<i64> {
+    jwt_claims(token)?.get("exp")?.as_i64()
+}
+
+/// Returns the signed-in user of the current request.
+#[cfg(feature = "server")]
+pub(crate) async fn current_user() -> Result<User, ServerFnError> {
+    let Extension(user): Extension<User> = extract()
+        .await
+        .map_err(|_| ServerFnError::new("not signed in"))?;
+    Ok(user)
+}
+
+/// Returns the signed-in user if their role has `access` under `permission`.
+#[cfg(feature = "server")]
+pub(crate) async fn authorize(
+    permission: Permission,
+    access: Access,
+) -> Result<User, ServerFnError> {
+    let user = current_user().await?;
+    if permission.allows(user.role, access) {
+        Ok(user)
+    } else {
+        Err(ServerFnError::new(format!(
+            "permission denied: {} cannot do this",
+            user.role
+        )))
+    }
+}
+
+#[cfg(feature = "server")]
+fn set_cookie(value: &str) -> Result<(), ServerFnError> {
+    let cookie = header::HeaderValue::from_str(value).map_err(ServerFnError::new)?;
+    server_context()
+        .response_parts_mut()
+        .headers
+        .insert(header::SET_COOKIE, cookie);
+    Ok(())
+}
+
+#[server(endpoint = "login")]
+async fn login(username: String, password: String) -> Result<(), ServerFnError> {
+    let Extension(profiles): Extension<Profiles> = extract().await?;
+    let Extension(sessions): Extension<Sessions> = extract().await?;
+
+    let account = profiles
+        .default_state()
+        .find_account(&username)
+        .await
+        .map_err(ServerFnError::new)?;
+    match account {
+        Some(account) if account.verify_password(&password) => {
+            let id = sessions.open(username).await;
+            set_cookie(&sessions.cookie(&id, "SameSite=Strict"))
+        }
+        _ => {
+            dioxus_logger::tracing::warn!("failed sign-in attempt for {username}");
+            Err(ServerFnError::new("invalid username or password"))
+        }
+    }
+}
+
+#[server(endpoint = "logout")]
+async fn logout() -> Result<(), ServerFnError> {
+    let Extension(sessions): Extension<Sessions> = extract().await?;
+    let headers = server_context().request_parts().headers.clone();
+    if let Some(id) = session_cookie(&headers) {
+        sessions.close(id).await;
+    }
+    set_cookie(&sessions.cookie("", "Max-Age=0"))
+}
+
+/// The signed-in account as seen by the browser.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Me {
+    pub(crate) username: String,
+    pub(crate) role: Role,
+}
+
+#[server]
+pub(crate) async fn whoami() -> Result<Me, ServerFnError> {
+    current_user().await.map(|user| Me {
+        username: user.username,
+        role: user.role,
+    })
+}
+
+#[component]
+pub fn Login() -> Element {
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let navigator = use_navigator();
+
+    rsx! {
+        div {
+            class: "relative rounded-3xl bg-white shadow-xl ring-1 ring-gray-900/5",
+            style: "margin: 2%; padding: 5%; max-width: 480px;",
+            h1 { style: "font: small-caps bold 24px sans-serif;", "Sign in to Deview" }
+            form {
+                style: "display: flex; flex-direction: column; gap: 8px;",
+                prevent_default: "onsubmit",
+                onsubmit: move |_| async move {
+                    match login(username(), password()).await {
+                        Ok(()) => {
+                            navigator.push(crate::Route::ProfileList {});
+                        }
+                        Err(e) => error.set(Some(e.to_string())),
+                    }
+                },
+                input {
+                    r#type: "text",
+                    placeholder: "Username",
+                    autocomplete: "username",
+                    value: "{username}",
+                    oninput: move |e| username.set(e.value())
+                }
+                input {
+                    r#type: "password",
+                    placeholder: "Password",
+                    autocomplete: "current-password",
+                    value: "{password}",
+                    oninput: move |e| password.set(e.value())
+                }
+                button { class: "hover:bg-gray-100", r#type: "submit", "Sign in" }
+            }
+            if let Some(e) = error() {
+                p { color: "red", "{e}" }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn UserMenu() -> Element {
+    let user = use_server_future(whoami)?;
+    let navigator = use_navigator();
+
+    rsx! {
+        div { style: "display: flex; flex-direction: row; align-items: center; gap: 8px;",
+            if let Some(Ok(me)) = user() {
+                span { title: "{me.role}", "{me.username}" }
+            }
+            button {
+                class: "hover:bg-gray-100",
+                onclick: move |_| async move {
+                    if logout().await.is_ok() {
+                        navigator.push(crate::Route::Login {});
+                    }
+                },
+                "Sign out"
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::{constant_time_eq, is_public};
+
+    #[test]
+    fn public_paths() {
+        assert!(is_public("/login"));
+        assert!(is_public("/api/login"));
+        assert!(is_public("/healthz"));
+        assert!(is_public("/favicon.ico"));
+        assert!(is_public("/assets/dioxus/deview_bg.wasm"));
+    }
+
+    #[test]
+    fn private_paths() {
+        assert!(!is_public("/"));
+        assert!(!is_public("/api/whoami"));
+        assert!(!is_public("/p/default/table/account.x"));
+        assert!(!is_public("/p/default/raw/accounts.json"));
+        assert!(!is_public("/assets/../p/default/raw"));
+    }
+
+    #[test]
+    fn compare_tokens() {
+        assert!(constant_time_eq(b"a.b.c", b"a.b.c"));
+        assert!(!constant_time_eq(b"a.b.c", b"a.b.d"));
+        assert!(!constant_time_eq(b"a.b.c", b"a.b.cd"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
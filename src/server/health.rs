@@ -0,0 +1,37 @@
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+use serde::Serialize;
+
+use super::Profiles;
+
+/// Answers as long as the process can serve requests.
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+#[derive(Serialize)]
+struct Readiness {
+    store_open: bool,
+    /// The contents of `VERSION` in the data directory, if it can be read.
+    version: Option<String>,
+    read_only: bool,
+    /// When a table was last read successfully, in RFC 3339.
+    last_read: Option<String>,
+}
+
+/// Reports whether the default profile is open and usable, answering with
+/// 503 Service Unavailable if its store is closed.
+pub async fn readyz(Extension(profiles): Extension<Profiles>) -> impl IntoResponse {
+    let state = profiles.default_state();
+    let readiness = Readiness {
+        store_open: state.is_open().await,
+        version: state.version().ok(),
+        read_only: state.is_read_only(),
+        last_read: state.last_read().map(|t| t.to_rfc3339()),
+    };
+    let status = if readiness.store_open {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
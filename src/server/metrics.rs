@@ -0,0 +1,272 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use dioxus::prelude::server_fn;
+
+use super::{
+    auth::{bearer_token, constant_time_eq},
+    rocks::ColumnFamilyStats,
+    LookUp, Profiles, State,
+};
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += secs;
+    }
+
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        for (count, bound) in self.buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+#[derive(Default)]
+struct ServerFn {
+    /// Responses by HTTP status code.
+    responses: BTreeMap<u16, u64>,
+    latency: Histogram,
+}
+
+static SERVER_FNS: Mutex<BTreeMap<String, ServerFn>> = Mutex::new(BTreeMap::new());
+static LOCK_WAITS: Mutex<BTreeMap<&'static str, Histogram>> = Mutex::new(BTreeMap::new());
+
+/// Records how long a request took to acquire the store lock, in `mode`
+/// "read" or "write".
+pub(crate) fn record_lock_wait(mode: &'static str, elapsed: Duration) {
+    if let Ok(mut waits) = LOCK_WAITS.lock() {
+        waits.entry(mode).or_default().observe(elapsed);
+    }
+}
+
+/// The token `/metrics` requires as `Authorization: Bearer <token>`. The
+/// metrics are not served at all without one.
+#[derive(Clone)]
+pub(crate) struct MetricsToken(pub(crate) Option<String>);
+
+/// Counts calls to registered server functions and measures how long they
+/// take. Other paths under `/api/` are not counted, so made-up paths cannot
+/// add label values without bound.
+pub async fn track_server_fns(req: Request, next: Next) -> Response {
+    static REGISTERED: OnceLock<BTreeSet<&'static str>> = OnceLock::new();
+
+    let registered = REGISTERED.get_or_init(|| {
+        server_fn::axum::server_fn_paths()
+            .map(|(path, _)| path)
+            .collect()
+    });
+    let path = req.uri().path();
+    let Some(function) = server_fn_name(path).filter(|_| registered.contains(path)) else {
+        return next.run(req).await;
+    };
+    let began = Instant::now();
+    let response = next.run(req).await;
+    if let Ok(mut server_fns) = SERVER_FNS.lock() {
+        let server_fn = server_fns.entry(function).or_default();
+        *server_fn
+            .responses
+            .entry(response.status().as_u16())
+            .or_default() += 1;
+        server_fn.latency.observe(began.elapsed());
+    }
+    response
+}
+
+/// Returns the name of the server function at `path`. Dioxus appends a hash
+/// to the names of server functions without an explicit endpoint.
+fn server_fn_name(path: &str) -> Option<String> {
+    let name = path
+        .strip_prefix("/api/")?
+        .trim_end_matches(|c: char| c.is_ascii_digit());
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Serves the metrics in the Prometheus text format to requests that bear
+/// the configured [`MetricsToken`].
+pub async fn metrics(
+    Extension(profiles): Extension<Profiles>,
+    Extension(MetricsToken(token)): Extension<MetricsToken>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(token) = token else {
+        return (StatusCode::NOT_FOUND, "metrics_token is not set").into_response();
+    };
+    if !bearer_token(&headers)
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    }
+    let mut out = String::new();
+    write_server_fns(&mut out);
+    write_lock_waits(&mut out);
+    // The statistics of each profile are read once, for both the table rows
+    // and the column families.
+    let mut stats = Vec::new();
+    for (profile, state) in profiles.open_states().await {
+        match state.column_family_stats().await {
+            Ok(cfs) => stats.push((profile, state, cfs)),
+            Err(e) => {
+                dioxus_logger::tracing::warn!("cannot read RocksDB statistics: {e:#}");
+            }
+        }
+    }
+    write_table_rows(&mut out, &stats).await;
+    write_column_families(&mut out, &stats);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out).into_response()
+}
+
+fn write_server_fns(out: &mut String) {
+    let Ok(server_fns) = SERVER_FNS.lock() else {
+        return;
+    };
+    let _ = writeln!(
+        out,
+        "# HELP deview_server_fn_requests_total Server function calls by response status."
+    );
+    let _ = writeln!(out, "# TYPE deview_server_fn_requests_total counter");
+    for (function, server_fn) in server_fns.iter() {
+        for (status, count) in &server_fn.responses {
+            let _ = writeln!(
+                out,
+                "deview_server_fn_requests_total{{function=\"{}\",status=\"{status}\"}} {count}",
+                escape(function)
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "# HELP deview_server_fn_duration_seconds How long server functions take."
+    );
+    let _ = writeln!(out, "# TYPE deview_server_fn_duration_seconds histogram");
+    for (function, server_fn) in server_fns.iter() {
+        server_fn.latency.write(
+            out,
+            "deview_server_fn_duration_seconds",
+            &format!("function=\"{}\"", escape(function)),
+        );
+    }
+}
+
+fn write_lock_waits(out: &mut String) {
+    let Ok(waits) = LOCK_WAITS.lock() else {
+        return;
+    };
+    let _ = writeln!(
+        out,
+        "# HELP deview_store_lock_wait_seconds Time spent waiting for the store lock."
+    );
+    let _ = writeln!(out, "# TYPE deview_store_lock_wait_seconds histogram");
+    for (mode, histogram) in waits.iter() {
+        histogram.write(
+            out,
+            "deview_store_lock_wait_seconds",
+            &format!("mode=\"{mode}\""),
+        );
+    }
+}
+
+async fn write_table_rows(out: &mut String, stats: &[(String, State, Vec<ColumnFamilyStats>)]) {
+    let _ = writeln!(
+        out,
+        "# HELP deview_table_rows Rows in each table, as RocksDB estimates them."
+    );
+    let _ = writeln!(out, "# TYPE deview_table_rows gauge");
+    for (profile, state, cfs) in stats {
+        for table in LookUp::iter() {
+            match state.estimated_rows(table, cfs).await {
+                Ok(None) => {}
+                Ok(Some(rows)) => {
+                    let _ = writeln!(
+                        out,
+                        "deview_table_rows{{profile=\"{}\",table=\"{table}\"}} {rows}",
+                        escape(profile)
+                    );
+                }
+                Err(e) => {
+                    dioxus_logger::tracing::warn!("cannot count rows of {table}: {e:#}");
+                }
+            }
+        }
+    }
+}
+
+fn write_column_families(out: &mut String, stats: &[(String, State, Vec<ColumnFamilyStats>)]) {
+    let mut estimated_keys = String::new();
+    let mut live_sst_size = String::new();
+    for (profile, _, cfs) in stats {
+        for cf in cfs {
+            let labels = format!(
+                "profile=\"{}\",column_family=\"{}\"",
+                escape(profile),
+                escape(&cf.name)
+            );
+            let _ = writeln!(
+                estimated_keys,
+                "deview_rocksdb_estimated_keys{{{labels}}} {}",
+                cf.estimated_keys
+            );
+            let _ = writeln!(
+                live_sst_size,
+                "deview_rocksdb_live_sst_bytes{{{labels}}} {}",
+                cf.live_sst_size
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "# HELP deview_rocksdb_estimated_keys Keys RocksDB estimates in each column family."
+    );
+    let _ = writeln!(out, "# TYPE deview_rocksdb_estimated_keys gauge");
+    out.push_str(&estimated_keys);
+    let _ = writeln!(
+        out,
+        "# HELP deview_rocksdb_live_sst_bytes Size of the live SST files of each column family."
+    );
+    let _ = writeln!(out, "# TYPE deview_rocksdb_live_sst_bytes gauge");
+    out.push_str(&live_sst_size);
+}
+
+/// Escapes a label value as the text format requires.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
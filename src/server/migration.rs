@@ -0,0 +1,233 @@
+#[cfg(feature = "server")]
+use anyhow::{Context, Result};
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{
+    auth::authorize,
+    permission::{ADMINISTRATORS, EVERYONE},
+    state::review,
+};
+use super::{permission::Access, whoami};
+
+/// The data format version of the review-database this build links against.
+/// review-database keeps its own copy private; the tests below fail when the
+/// version in `Cargo.lock` moves without this one.
+#[cfg(feature = "server")]
+const EXPECTED_VERSION: &str = "0.30.0";
+#[cfg(feature = "server")]
+const COMPATIBLE_VERSION_REQ: &str = ">=0.30.0,<0.31.0-alpha";
+/// The migration steps of review-database, as the versions a step applies to
+/// and the version it leaves behind.
+#[cfg(feature = "server")]
+const MIGRATIONS: &[(&str, &str)] = &[
+    (">=0.25.0,<0.26.0", "0.26.0"),
+    (">=0.26.0,<0.28.0", "0.28.0"),
+    (">=0.28.0,<0.29.0", "0.29.0"),
+    (">=0.29.0,<0.30.0", "0.30.0"),
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MigrationStatus {
+    on_disk: String,
+    expected: String,
+    /// The steps `migrate_data_dir` would run, e.g. "0.28.0 → 0.29.0".
+    steps: Vec<String>,
+    up_to_date: bool,
+    /// Whether review-database knows how to migrate the on-disk version.
+    supported: bool,
+    read_only: bool,
+}
+
+#[cfg(feature = "server")]
+impl MigrationStatus {
+    /// Plans the migration of a data directory at version `on_disk`.
+    pub(crate) fn new(on_disk: String) -> Result<Self> {
+        let compatible = VersionReq::parse(COMPATIBLE_VERSION_REQ)?;
+        let mut version = Version::parse(&on_disk)
+            .with_context(|| format!("invalid version \"{on_disk}\" in VERSION"))?;
+        let up_to_date = compatible.matches(&version);
+        let mut steps = Vec::new();
+        while !compatible.matches(&version) {
+            let mut next = None;
+            for (req, to) in MIGRATIONS {
+                if VersionReq::parse(req)?.matches(&version) {
+                    next = Some(Version::parse(to)?);
+                    break;
+                }
+            }
+            let Some(next) = next else {
+                break;
+            };
+            steps.push(format!("{version} → {next}"));
+            version = next;
+        }
+        Ok(Self {
+            on_disk,
+            expected: EXPECTED_VERSION.to_string(),
+            steps,
+            up_to_date,
+            supported: compatible.matches(&version),
+            read_only: false,
+        })
+    }
+
+    /// Fails unless the data directory can be opened as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming both versions if the data directory needs a
+    /// migration first.
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.up_to_date {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "the data directory is at version {}, but {} is expected; migrate it first",
+                self.on_disk,
+                self.expected
+            ))
+        }
+    }
+}
+
+#[server]
+async fn migration_status(profile: String) -> Result<MigrationStatus, ServerFnError> {
+    authorize(EVERYONE, Access::Read).await?;
+    let review = review(&profile).await?;
+    let on_disk = review.version().map_err(ServerFnError::new)?;
+    let mut status = MigrationStatus::new(on_disk).map_err(ServerFnError::new)?;
+    status.read_only = review.is_read_only();
+    Ok(status)
+}
+
+#[server]
+async fn migrate_now(profile: String) -> Result<(), ServerFnError> {
+    let user = authorize(ADMINISTRATORS, Access::Write).await?;
+    let review = review(&profile).await?;
+    review.check_writable().map_err(ServerFnError::new)?;
+    dioxus_logger::tracing::info!(
+        "{} started migrating the data directory of profile {profile}",
+        user.username
+    );
+    review.migrate().await.map_err(ServerFnError::new)
+}
+
+/// Shown on the home page when the data directory was opened without
+/// migration and is older than this build expects.
+#[component]
+pub fn Migration(profile: String) -> Element {
+    let mut status = use_server_future(use_reactive((&profile,), |(profile,)| {
+        migration_status(profile)
+    }))?;
+    let me = use_server_future(whoami)?;
+    let mut result = use_signal(|| None::<String>);
+
+    let can_migrate = matches!(
+        me(),
+        Some(Ok(me)) if super::permission::ADMINISTRATORS.allows(me.role, Access::Write)
+    );
+    let Some(Ok(status_now)) = status() else {
+        return rsx! {};
+    };
+    if status_now.up_to_date {
+        return rsx! {};
+    }
+    rsx! {
+        div {
+            class: "rounded-3xl bg-white shadow-xl ring-1 ring-gray-900/5",
+            style: "margin: 2%; padding: 2%; border: 2px solid orange;",
+            h1 { style: "font: small-caps bold 24px sans-serif;", "Migration pending" }
+            p {
+                "The data directory is at version {status_now.on_disk}, "
+                "but this build expects {status_now.expected}."
+            }
+            if status_now.supported {
+                p { "Migrating runs these steps:" }
+                ol {
+                    for step in status_now.steps.iter() {
+                        li { "{step}" }
+                    }
+                }
+                if status_now.read_only {
+                    p { "Migration is disabled in read-only mode." }
+                } else if can_migrate {
+                    button {
+                        class: "hover:bg-gray-100",
+                        style: "border: 1px solid rgba(0, 0, 0, 0.5); padding: 4px;",
+                        onclick: move |_| {
+                            let profile = profile.clone();
+                            async move {
+                                match migrate_now(profile).await {
+                                    Ok(()) => result.set(Some("Migration finished.".to_string())),
+                                    Err(e) => result.set(Some(format!("Migration failed: {e}"))),
+                                }
+                                status.restart();
+                            }
+                        },
+                        "Migrate now"
+                    }
+                }
+            } else {
+                p { "review-database cannot migrate from this version." }
+            }
+            if let Some(result) = result() {
+                p { "{result}" }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use semver::{Version, VersionReq};
+
+    use super::{MigrationStatus, COMPATIBLE_VERSION_REQ, EXPECTED_VERSION, MIGRATIONS};
+
+    /// Returns the version of review-database recorded in `Cargo.lock`.
+    fn locked_version() -> String {
+        let lock = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.lock"))
+            .expect("Cargo.lock is readable");
+        let mut lines = lock
+            .lines()
+            .skip_while(|l| *l != "name = \"review-database\"");
+        lines
+            .nth(1)
+            .and_then(|l| l.strip_prefix("version = \""))
+            .and_then(|l| l.strip_suffix('"'))
+            .expect("review-database is locked")
+            .to_string()
+    }
+
+    #[test]
+    fn expected_version_is_locked() {
+        assert_eq!(EXPECTED_VERSION, locked_version());
+    }
+
+    #[test]
+    fn migrations_end_at_expected_version() {
+        let expected = Version::parse(EXPECTED_VERSION).unwrap();
+        assert!(VersionReq::parse(COMPATIBLE_VERSION_REQ)
+            .unwrap()
+            .matches(&expected));
+        assert_eq!(MIGRATIONS.last().map(|(_, to)| *to), Some(EXPECTED_VERSION));
+    }
+
+    #[test]
+    fn plan_from_oldest_supported() {
+        let status = MigrationStatus::new("0.25.0".to_string()).unwrap();
+        assert!(status.supported);
+        assert!(!status.up_to_date);
+        assert_eq!(status.steps.len(), MIGRATIONS.len());
+
+        let status = MigrationStatus::new(EXPECTED_VERSION.to_string()).unwrap();
+        assert!(status.up_to_date);
+        assert!(status.steps.is_empty());
+
+        let status = MigrationStatus::new("0.24.0".to_string()).unwrap();
+        assert!(!status.supported);
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// The role of a REview account, mirroring `review_database::types::Role`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+)]
+pub(crate) enum Role {
+    #[strum(serialize = "System Administrator")]
+    SystemAdministrator,
+    #[strum(serialize = "Security Administrator")]
+    SecurityAdministrator,
+    #[strum(serialize = "Security Manager")]
+    SecurityManager,
+    #[strum(serialize = "Security Monitor")]
+    SecurityMonitor,
+}
+
+#[cfg(feature = "server")]
+impl From<review_database::types::Role> for Role {
+    fn from(role: review_database::types::Role) -> Self {
+        use review_database::types::Role as R;
+
+        match role {
+            R::SystemAdministrator => Self::SystemAdministrator,
+            R::SecurityAdministrator => Self::SecurityAdministrator,
+            R::SecurityManager => Self::SecurityManager,
+            R::SecurityMonitor => Self::SecurityMonitor,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<Role> for review_database::types::Role {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::SystemAdministrator => Self::SystemAdministrator,
+            Role::SecurityAdministrator => Self::SecurityAdministrator,
+            Role::SecurityManager => Self::SecurityManager,
+            Role::SecurityMonitor => Self::SecurityMonitor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Access {
+    Read,
+    Write,
+}
+
+/// The roles allowed to read and to modify a table.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Permission {
+    pub(crate) read: &'static [Role],
+    pub(crate) write: &'static [Role],
+}
+
+impl Permission {
+    pub(crate) fn allows(&self, role: Role, access: Access) -> bool {
+        match access {
+            Access::Read => self.read.contains(&role) || self.write.contains(&role),
+            Access::Write => self.write.contains(&role),
+        }
+    }
+}
+
+/// Information only administrators can see, such as access tokens.
+pub(crate) const ADMINISTRATORS: Permission = Permission {
+    read: &[Role::SystemAdministrator, Role::SecurityAdministrator],
+    write: &[Role::SystemAdministrator],
+};
+
+/// Information about the people using REview, which security managers can
+/// see as well.
+pub(crate) const MANAGERS: Permission = Permission {
+    read: &[
+        Role::SystemAdministrator,
+        Role::SecurityAdministrator,
+        Role::SecurityManager,
+    ],
+    write: &[Role::SystemAdministrator],
+};
+
+/// Information every signed-in account can see.
+pub(crate) const EVERYONE: Permission = Permission {
+    read: &[
+        Role::SystemAdministrator,
+        Role::SecurityAdministrator,
+        Role::SecurityManager,
+        Role::SecurityMonitor,
+    ],
+    write: &[Role::SystemAdministrator],
+};
@@ -0,0 +1,357 @@
+#[cfg(feature = "server")]
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+#[cfg(feature = "server")]
+use anyhow::{anyhow, Result};
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use dioxus_logger::tracing;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tokio::sync::{Mutex, RwLock};
+
+#[cfg(feature = "server")]
+use super::{
+    auth::authorize,
+    permission::{ADMINISTRATORS, EVERYONE},
+    state::{blocking, OpenOptions, State},
+};
+use super::{permission::Access, whoami};
+#[cfg(feature = "server")]
+use crate::config::Profile;
+use crate::Route;
+
+/// The name of the profile formed by `data_dir` and `backup_dir` in the
+/// configuration. Sign-in is checked against its accounts.
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+/// Joins a profile name and a backup ID into the name of the profile that
+/// browses the backup. Configured names cannot contain it.
+const BACKUP_SEPARATOR: &str = "~backup-";
+
+/// Returns the name of the profile that browses backup `id` of `profile`.
+#[cfg(feature = "server")]
+fn backup_profile(profile: &str, id: u32) -> String {
+    format!("{profile}{BACKUP_SEPARATOR}{id}")
+}
+
+/// Returns the profile and the backup ID that `name` browses, if it names a
+/// browsed backup.
+pub(crate) fn browsed_backup(name: &str) -> Option<(&str, u32)> {
+    let (profile, id) = name.rsplit_once(BACKUP_SEPARATOR)?;
+    Some((profile, id.parse().ok()?))
+}
+
+/// The data directories Deview can show, each opened on first use.
+#[cfg(feature = "server")]
+#[derive(Clone)]
+pub struct Profiles {
+    /// The default profile, open from start to shutdown.
+    default: State,
+    configured: Arc<Vec<Profile>>,
+    options: OpenOptions,
+    refresh_interval: Option<Duration>,
+    open: Arc<RwLock<HashMap<String, State>>>,
+    /// Held while a profile is opened or closed, so a data directory is
+    /// never opened twice, without keeping `open` locked meanwhile.
+    opening: Arc<Mutex<()>>,
+}
+
+#[cfg(feature = "server")]
+impl Profiles {
+    pub fn new(
+        default: State,
+        configured: Vec<Profile>,
+        options: OpenOptions,
+        refresh_interval: Option<Duration>,
+    ) -> Self {
+        Self {
+            default,
+            configured: Arc::new(configured),
+            options,
+            refresh_interval,
+            open: Arc::default(),
+            opening: Arc::default(),
+        }
+    }
+
+    pub fn default_state(&self) -> &State {
+        &self.default
+    }
+
+    /// Returns the state of profile `name`, opening it first if needed. The
+    /// profile is opened on a blocking thread, and migrated only if its
+    /// configuration says so.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no profile is named `name` or its data directory
+    /// cannot be opened.
+    pub async fn get(&self, name: &str) -> Result<State> {
+        if name == DEFAULT_PROFILE {
+            return Ok(self.default.clone());
+        }
+        if let Some(state) = self.open.read().await.get(name) {
+            return Ok(state.clone());
+        }
+        let profile = self
+            .configured
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| anyhow!("unknown profile \"{name}\""))?;
+        let _opening = self.opening.lock().await;
+        if let Some(state) = self.open.read().await.get(name) {
+            return Ok(state.clone());
+        }
+        let (data_dir, backup_dir) = (profile.data_dir.clone(), profile.backup_dir.clone());
+        let options = OpenOptions {
+            migrate: profile.migrate,
+            ..self.options.clone()
+        };
+        let state = blocking(move || State::open(&data_dir, &backup_dir, &options)).await?;
+        if let Some(interval) = self.refresh_interval {
+            tokio::spawn(state.clone().catch_up_every(interval));
+        }
+        tracing::info!("opened profile {name}");
+        self.open
+            .write()
+            .await
+            .insert(name.to_string(), state.clone());
+        Ok(state)
+    }
+
+    /// Restores backup `id` of profile `profile` into a scratch directory
+    /// and opens it read-only as a profile of its own, returning its name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `profile` cannot be opened or the backup cannot
+    /// be restored.
+    pub async fn browse(&self, profile: &str, id: u32) -> Result<String> {
+        let name = backup_profile(profile, id);
+        if self.open.read().await.contains_key(&name) {
+            return Ok(name);
+        }
+        let state = self
+            .get(profile)
+            .await?
+            .browse_backup(id, self.options.scratch.as_deref())
+            .await?;
+        let mut open = self.open.write().await;
+        if open.contains_key(&name) {
+            // Someone else restored it first; closing this copy deletes it.
+            drop(open);
+            state.close().await;
+            return Ok(name);
+        }
+        tracing::info!("opened backup {id} of profile {profile}");
+        open.insert(name.clone(), state);
+        Ok(name)
+    }
+
+    /// Closes profile `name`. Requests still using it fail with "the store
+    /// is closed"; the next request opens it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the default profile, which stays open.
+    pub async fn close(&self, name: &str) -> Result<()> {
+        if name == DEFAULT_PROFILE {
+            return Err(anyhow!("the default profile cannot be closed"));
+        }
+        // The profile stays listed until its store is closed, so it is not
+        // opened again while the data directory is still locked.
+        let _opening = self.opening.lock().await;
+        let state = self.open.read().await.get(name).cloned();
+        if let Some(state) = state {
+            state.close().await;
+            self.open.write().await.remove(name);
+            tracing::info!("closed profile {name}");
+        }
+        Ok(())
+    }
+
+    /// Closes every profile, including the default one.
+    pub async fn close_all(&self) {
+        let _opening = self.opening.lock().await;
+        let open: Vec<State> = self.open.read().await.values().cloned().collect();
+        for state in open {
+            state.close().await;
+        }
+        self.open.write().await.clear();
+        self.default.close().await;
+    }
+
+    /// Returns the profiles that are open now, the default one first.
+    pub async fn open_states(&self) -> Vec<(String, State)> {
+        let open = self.open.read().await;
+        let mut states = vec![(DEFAULT_PROFILE.to_string(), self.default.clone())];
+        states.extend(
+            open.iter()
+                .map(|(name, state)| (name.clone(), state.clone())),
+        );
+        states
+    }
+
+    async fn list(&self) -> Vec<ProfileInfo> {
+        let open = self.open.read().await;
+        let mut profiles = vec![ProfileInfo {
+            name: DEFAULT_PROFILE.to_string(),
+            data_dir: self.default.data_dir().display().to_string(),
+            open: true,
+        }];
+        profiles.extend(self.configured.iter().map(|p| ProfileInfo {
+            name: p.name.clone(),
+            data_dir: p.data_dir.display().to_string(),
+            open: open.contains_key(&p.name),
+        }));
+        let mut browsed: Vec<ProfileInfo> = open
+            .iter()
+            .filter(|(name, _)| browsed_backup(name).is_some())
+            .map(|(name, state)| ProfileInfo {
+                name: name.clone(),
+                data_dir: state.data_dir().display().to_string(),
+                open: true,
+            })
+            .collect();
+        browsed.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        profiles.extend(browsed);
+        profiles
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ProfileInfo {
+    name: String,
+    data_dir: String,
+    open: bool,
+}
+
+#[server]
+async fn profiles() -> Result<Vec<ProfileInfo>, ServerFnError> {
+    use axum::Extension;
+
+    authorize(EVERYONE, Access::Read).await?;
+    let Extension(profiles): Extension<Profiles> = extract().await?;
+    Ok(profiles.list().await)
+}
+
+#[server]
+async fn close_profile(name: String) -> Result<(), ServerFnError> {
+    use axum::Extension;
+
+    let user = authorize(ADMINISTRATORS, Access::Write).await?;
+    let Extension(profiles): Extension<Profiles> = extract().await?;
+    tracing::info!("{} closes profile {name}", user.username);
+    profiles.close(&name).await.map_err(ServerFnError::new)
+}
+
+/// Shows the active profile in the header and switches to another one.
+#[component]
+pub fn ProfileSwitcher(profile: Option<String>) -> Element {
+    let list = use_server_future(profiles)?;
+    let navigator = use_navigator();
+    let Some(Ok(profiles)) = list() else {
+        return rsx! {};
+    };
+    rsx! {
+        select {
+            title: "Profile",
+            onchange: move |e| {
+                navigator.push(Route::Home { profile: e.value() });
+            },
+            if profile.is_none() {
+                option { disabled: true, selected: true, "Choose a profile" }
+            }
+            for p in profiles {
+                option {
+                    value: "{p.name}",
+                    selected: profile.as_deref() == Some(p.name.as_str()),
+                    "{p.name}"
+                }
+            }
+        }
+    }
+}
+
+/// Lists the profiles, opening one when it is followed and closing it on
+/// request.
+#[component]
+pub fn ProfileList() -> Element {
+    let mut list = use_server_future(profiles)?;
+    let me = use_server_future(whoami)?;
+    let mut error = use_signal(|| None::<String>);
+
+    let can_close = matches!(
+        me(),
+        Some(Ok(me)) if super::permission::ADMINISTRATORS.allows(me.role, Access::Write)
+    );
+    rsx! {
+        crate::components::Header {}
+        div {
+            class: "rounded-3xl bg-white shadow-xl ring-1 ring-gray-900/5",
+            style: "margin: 2%; padding: 5%;",
+            table { style: "border-spacing: 0;",
+                caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
+                    "Profiles"
+                }
+                thead {
+                    tr { style: "background: rgba(0, 0, 0, 0.1);",
+                        th { scope: "col", "Name" }
+                        th { scope: "col", "Data Directory" }
+                        th { scope: "col", "Status" }
+                        th { scope: "col" }
+                    }
+                }
+                tbody {
+                    match list() {
+                        None => rsx! { tr { td { colspan: 4, "Loading..." } } },
+                        Some(Err(e)) => rsx! { tr { td { colspan: 4, "{e}" } } },
+                        Some(Ok(entries)) => rsx! {
+                            for entry in entries {
+                                tr { class: "odd:bg-white even:bg-gray-100",
+                                    th {
+                                        Link {
+                                            class: "hover:bg-gray-100",
+                                            to: Route::Home { profile: entry.name.clone() },
+                                            "{entry.name}"
+                                        }
+                                    }
+                                    td { "{entry.data_dir}" }
+                                    td {
+                                        if entry.open { "open" } else { "closed" }
+                                    }
+                                    td {
+                                        if can_close && entry.open && entry.name != DEFAULT_PROFILE {
+                                            button {
+                                                class: "hover:bg-gray-100",
+                                                style: "border: 1px solid rgba(0, 0, 0, 0.5); padding: 0 4px;",
+                                                onclick: {
+                                                    let name = entry.name.clone();
+                                                    move |_| {
+                                                        let name = name.clone();
+                                                        async move {
+                                                            if let Err(e) = close_profile(name).await {
+                                                                error.set(Some(e.to_string()));
+                                                            }
+                                                            list.restart();
+                                                        }
+                                                    }
+                                                },
+                                                "Close"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+            if let Some(e) = error() {
+                p { color: "red", "{e}" }
+            }
+        }
+        crate::components::Footer {}
+    }
+}
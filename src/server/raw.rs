@@ -0,0 +1,314 @@
+#[cfg(feature = "server")]
+use anyhow::Result;
+#[cfg(feature = "server")]
+use data_encoding::HEXLOWER;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    permission::{Permission, ADMINISTRATORS},
+    tables::{Cursor, Failures, Page, Pager},
+};
+#[cfg(feature = "server")]
+use super::{
+    tables::{read_raw_page, LookUp},
+    State,
+};
+
+/// The roles that may browse column families. Raw values include what the
+/// table pages hide, so only administrators may.
+pub(crate) const PERMISSION: Permission = ADMINISTRATORS;
+
+/// How many entries a column family page shows.
+const PAGE_SIZE: usize = 50;
+
+/// How many bytes of a key or value are shown in hex.
+const MAX_SHOWN: usize = 256;
+
+/// A column family in the column family list.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ColumnFamilyInfo {
+    name: String,
+    estimated_keys: u64,
+    live_sst_size: u64,
+    /// The table stored in the column family, if Deview knows it.
+    table: Option<String>,
+}
+
+/// A key-value pair as stored in RocksDB.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RawEntry {
+    key: Bytes,
+    value: Bytes,
+    /// The value decoded as the table in the column family, if Deview knows
+    /// it.
+    decoded: Option<Result<String, String>>,
+}
+
+/// Raw bytes in hex, and as text if they are valid UTF-8.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Bytes {
+    /// The first [`MAX_SHOWN`] bytes.
+    hex: String,
+    len: usize,
+    /// The first [`MAX_SHOWN`] characters, if the bytes are valid UTF-8.
+    text: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl Bytes {
+    fn new(bytes: &[u8]) -> Self {
+        Self {
+            hex: HEXLOWER.encode(&bytes[..bytes.len().min(MAX_SHOWN)]),
+            len: bytes.len(),
+            text: std::str::from_utf8(bytes).ok().map(|text| {
+                let mut shown: String = text
+                    .chars()
+                    .take(MAX_SHOWN)
+                    .flat_map(char::escape_debug)
+                    .collect();
+                if text.chars().nth(MAX_SHOWN).is_some() {
+                    shown.push('…');
+                }
+                shown
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl State {
+    /// Reads up to `size` raw entries of column family `cf` at `cursor`.
+    pub(crate) async fn raw_page(
+        &self,
+        cf: &str,
+        cursor: &Cursor,
+        size: usize,
+    ) -> Result<Page<RawEntry>> {
+        let table = LookUp::for_column_family(cf);
+        let (cf, cursor) = (cf.to_string(), cursor.clone());
+        let page = self
+            .with_db(move |db| {
+                read_raw_page(table, &cursor, size, |direction, from, f| {
+                    db.scan(&cf, direction, from, &mut |key, value| {
+                        f(Some(key.clone()), Ok((key, value)))
+                    })
+                })
+            })
+            .await?;
+        self.mark_read();
+        Ok(page.map(|(key, value)| RawEntry {
+            key: Bytes::new(&table.map_or(key, |table| table.mask_key(&key))),
+            decoded: table
+                .and_then(|table| table.decode_value(&value))
+                .map(|decoded| decoded.map_err(|e| format!("{e:#}"))),
+            value: Bytes::new(&value),
+        }))
+    }
+}
+
+#[server]
+async fn column_families(profile: String) -> Result<Vec<ColumnFamilyInfo>, ServerFnError> {
+    use super::{auth::authorize, permission::Access, state::review};
+
+    authorize(PERMISSION, Access::Read).await?;
+    let review = review(&profile).await?;
+
+    let stats = review
+        .column_family_stats()
+        .await
+        .map_err(ServerFnError::new)?;
+    Ok(stats
+        .into_iter()
+        .map(|cf| ColumnFamilyInfo {
+            table: LookUp::for_column_family(&cf.name).map(|t| t.to_string()),
+            name: cf.name,
+            estimated_keys: cf.estimated_keys,
+            live_sst_size: cf.live_sst_size,
+        })
+        .collect())
+}
+
+#[server]
+async fn raw_page(
+    profile: String,
+    cf: String,
+    cursor: Cursor,
+    size: usize,
+) -> Result<Page<RawEntry>, ServerFnError> {
+    use super::{auth::authorize, permission::Access, state::review};
+
+    authorize(PERMISSION, Access::Read).await?;
+    let review = review(&profile).await?;
+
+    review
+        .raw_page(&cf, &cursor, size.min(PAGE_SIZE))
+        .await
+        .map_err(ServerFnError::new)
+}
+
+/// Formats a size in bytes with a binary prefix.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Lists the column families in the data directory, including those no
+/// table page shows.
+#[component]
+pub fn ColumnFamilies(profile: String) -> Element {
+    let families = use_server_future(use_reactive((&profile,), |(profile,)| {
+        column_families(profile)
+    }))?;
+    rsx! {
+        table { style: "table-layout: fixed;
+                max-width: 1200px; max-height: 1200px;
+                overflow: auto; display: block;
+                border-spacing: 0;",
+            caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
+                "Column Families"
+            }
+            thead {
+                tr { style: "position: sticky; top: 0; background: rgba(0, 0, 0, 0.1);",
+                    th { scope: "col", "Name" }
+                    th { scope: "col", "Table" }
+                    th { scope: "col", "Estimated Keys" }
+                    th { scope: "col", "SST Size" }
+                }
+            }
+            tbody {
+                match families() {
+                    None => rsx! { tr { td { colspan: 4, "Loading..." } } },
+                    Some(Err(e)) => rsx! { tr { td { colspan: 4, "{e}" } } },
+                    Some(Ok(families)) => rsx! {
+                        for cf in families {
+                            tr { class: "odd:bg-white even:bg-gray-100",
+                                th { scope: "row",
+                                    Link {
+                                        class: "hover:bg-gray-100",
+                                        to: crate::Route::ColumnFamily {
+                                            profile: profile.clone(),
+                                            name: cf.name.clone(),
+                                            after: String::new(),
+                                            before: String::new(),
+                                        },
+                                        "{cf.name}"
+                                    }
+                                }
+                                td {
+                                    if let Some(table) = cf.table {
+                                        Link {
+                                            class: "hover:bg-gray-100",
+                                            to: crate::Route::Table {
+                                                profile: profile.clone(),
+                                                name: table.clone(),
+                                                after: String::new(),
+                                                before: String::new(),
+                                            },
+                                            "{table}"
+                                        }
+                                    }
+                                }
+                                td { style: "text-align: right;", "≈{cf.estimated_keys}" }
+                                td { style: "text-align: right;", title: "{cf.live_sst_size} bytes",
+                                    {format_size(cf.live_sst_size)}
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn BytesCell(bytes: Bytes) -> Element {
+    rsx! {
+        td { style: "vertical-align: top;",
+            p { style: "font-family: monospace; word-break: break-all;",
+                "{bytes.hex}"
+                if bytes.len > MAX_SHOWN {
+                    span { style: "color: gray;", " … ({bytes.len} bytes)" }
+                }
+            }
+            if let Some(text) = bytes.text {
+                p { style: "color: gray; word-break: break-word;", "{text}" }
+            }
+        }
+    }
+}
+
+/// Pages through the raw entries of a column family.
+#[component]
+pub fn ColumnFamily(profile: String, name: String, after: String, before: String) -> Element {
+    let cursor = Cursor::new(&after, &before);
+    let page = use_server_future(use_reactive(
+        (&profile, &name, &cursor),
+        |(profile, name, cursor)| raw_page(profile, name, cursor, PAGE_SIZE),
+    ))?;
+    let link = |cursor: Cursor| {
+        let (after, before) = cursor.to_query();
+        crate::Route::ColumnFamily {
+            profile: profile.clone(),
+            name: name.clone(),
+            after,
+            before,
+        }
+    };
+    rsx! {
+        if let Some(Ok(page)) = page() {
+            Failures { failures: page.failures }
+        }
+        table { style: "table-layout: fixed;
+                max-width: 1200px; max-height: 1200px;
+                overflow: auto; display: block;
+                border-spacing: 0;",
+            caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
+                "{name}"
+            }
+            thead {
+                tr { style: "position: sticky; top: 0; background: rgba(0, 0, 0, 0.1);",
+                    th { style: "width: 30%;", scope: "col", "Key" }
+                    th { style: "width: 40%;", scope: "col", "Value" }
+                    th { style: "width: 30%;", scope: "col", "Decoded" }
+                }
+            }
+            tbody {
+                match page() {
+                    None => rsx! { tr { td { colspan: 3, "Loading..." } } },
+                    Some(Err(e)) => rsx! { tr { td { colspan: 3, "{e}" } } },
+                    Some(Ok(page)) => rsx! {
+                        for entry in page.rows {
+                            tr { class: "odd:bg-white even:bg-gray-100",
+                                BytesCell { bytes: entry.key }
+                                BytesCell { bytes: entry.value }
+                                td { style: "vertical-align: top; word-break: break-word;",
+                                    match entry.decoded {
+                                        None => rsx! { span { style: "color: gray;", "unknown type" } },
+                                        Some(Ok(decoded)) => rsx! { "{decoded}" },
+                                        Some(Err(e)) => rsx! { span { style: "color: darkorange;", "{e}" } },
+                                    }
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+        if let Some(Ok(page)) = page() {
+            Pager { prev: page.prev.map(&link), next: page.next.map(&link) }
+        }
+    }
+}
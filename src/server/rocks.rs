@@ -0,0 +1,369 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    ops::ControlFlow,
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use review_database::Direction;
+use rocksdb::{properties, ColumnFamily, IteratorMode, Options, DB};
+use tempfile::TempDir;
+
+/// The RocksDB database review-database keeps in a data directory.
+pub(crate) const DB_DIR: &str = "states.db";
+
+/// What RocksDB estimates about one column family.
+pub(crate) struct ColumnFamilyStats {
+    pub(crate) name: String,
+    pub(crate) estimated_keys: u64,
+    pub(crate) live_sst_size: u64,
+}
+
+/// A RocksDB secondary instance of the database behind a store. It reads
+/// what the store, or a REview running on the same directory, has written
+/// without taking the database lock, and is kept open between reads.
+pub(crate) struct Db {
+    db: DB,
+    names: Vec<String>,
+    /// Where the secondary instance keeps its own log files. Declared after
+    /// `db` so the database is closed before the directory is deleted.
+    _dir: TempDir,
+}
+
+impl Db {
+    /// Opens the database at `path` as a secondary instance, which keeps its
+    /// own log files in a new directory under `scratch`, or else under the
+    /// system's temporary directory.
+    pub(crate) fn open(path: &Path, scratch: Option<&Path>) -> Result<Self> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("deview-secondary-");
+        let dir = match scratch {
+            Some(scratch) => builder.tempdir_in(scratch),
+            None => builder.tempdir(),
+        }
+        .context("cannot create a directory for a secondary instance")?;
+        let mut opts = Options::default();
+        // A secondary instance keeps every table file open, so files the
+        // primary deletes after a compaction stay readable until it catches
+        // up.
+        opts.set_max_open_files(-1);
+        let names = DB::list_cf(&opts, path)?;
+        let db = DB::open_cf_as_secondary(&opts, path, dir.path(), &names)
+            .with_context(|| format!("cannot open {}", path.display()))?;
+        Ok(Self {
+            db,
+            names,
+            _dir: dir,
+        })
+    }
+
+    /// Reads what the primary has written since the last catch-up.
+    pub(crate) fn catch_up(&self) -> Result<()> {
+        self.db.try_catch_up_with_primary()?;
+        Ok(())
+    }
+
+    fn cf(&self, name: &str) -> Result<&ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| anyhow!("no column family named {name}"))
+    }
+
+    /// Reads the statistics of every column family.
+    pub(crate) fn column_family_stats(&self) -> Result<Vec<ColumnFamilyStats>> {
+        self.names
+            .iter()
+            .map(|name| {
+                let cf = self.cf(name)?;
+                let estimated_keys = self
+                    .db
+                    .property_int_value_cf(cf, properties::ESTIMATE_NUM_KEYS)?
+                    .unwrap_or_default();
+                let live_sst_size = self
+                    .db
+                    .property_int_value_cf(cf, properties::LIVE_SST_FILES_SIZE)?
+                    .unwrap_or_default();
+                Ok(ColumnFamilyStats {
+                    name: name.clone(),
+                    estimated_keys,
+                    live_sst_size,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads the raw value stored under `key` in column family `cf`.
+    pub(crate) fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.db
+            .get_cf(self.cf(cf)?, key)
+            .with_context(|| format!("cannot read {cf}"))
+    }
+
+    /// Calls `f` with the raw keys and values of column family `cf`, in
+    /// `direction` from key `from`, until `f` breaks.
+    pub(crate) fn scan(
+        &self,
+        cf: &str,
+        direction: Direction,
+        from: Option<&[u8]>,
+        f: &mut dyn FnMut(Vec<u8>, Vec<u8>) -> ControlFlow<()>,
+    ) -> Result<()> {
+        for entry in self.db.iterator_cf(self.cf(cf)?, mode(from, direction)) {
+            let (key, value) = entry.with_context(|| format!("cannot read {cf}"))?;
+            if f(key.into_vec(), value.into_vec()).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterates in `direction` from key `from`, or from the first key in that
+/// direction, the way review-database's `Iterable::iter` does.
+fn mode(from: Option<&[u8]>, direction: Direction) -> IteratorMode<'_> {
+    match (from, direction) {
+        (None, Direction::Forward) => IteratorMode::Start,
+        (None, Direction::Reverse) => IteratorMode::End,
+        (Some(key), Direction::Forward) => IteratorMode::From(key, rocksdb::Direction::Forward),
+        (Some(key), Direction::Reverse) => IteratorMode::From(key, rocksdb::Direction::Reverse),
+    }
+}
+
+/// Lists the IDs of the backups in the backup engine directory `path`, in
+/// ascending order, by the names of their meta files.
+pub(crate) fn backup_ids(path: &Path) -> Result<Vec<u32>> {
+    let meta = path.join("meta");
+    let entries = match fs::read_dir(&meta) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("cannot read {}", meta.display())),
+    };
+    let mut ids = Vec::new();
+    for entry in entries {
+        // Skips the temporary files meta files are written through.
+        if let Some(id) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// A backup as its meta file records it.
+pub(crate) struct BackupMeta {
+    /// When the backup was taken, in seconds since the Unix epoch.
+    pub(crate) timestamp: i64,
+    /// The files of the backup, relative to the backup engine directory,
+    /// with their CRC32C checksums.
+    pub(crate) files: Vec<(String, u32)>,
+}
+
+impl BackupMeta {
+    /// Reads the meta file of backup `id` in the backup engine directory
+    /// `path`.
+    ///
+    /// Backups are read this way rather than through a backup engine, which
+    /// would lock the directory against the store's own, and which a
+    /// read-only state cannot open at all. For the same reason they are
+    /// verified by [`BackupMeta::verify`] rather than
+    /// `BackupEngine::verify_backup`, which also checks only file sizes.
+    pub(crate) fn read(path: &Path, id: u32) -> Result<Self> {
+        let meta = fs::read_to_string(path.join("meta").join(id.to_string()))
+            .with_context(|| format!("cannot read the meta file of backup {id}"))?;
+        Self::parse(&meta)
+    }
+
+    /// Parses a meta file, which lists the files of a backup after the time
+    /// of the backup, its sequence number, its optional metadata and the
+    /// number of files, one per line as `<file> crc32 <checksum>`.
+    ///
+    /// Meta files of schema version 1 have no version line. Version 2, which
+    /// RocksDB writes only when asked to, adds fields this parser skips;
+    /// later versions are refused rather than misread.
+    fn parse(meta: &str) -> Result<Self> {
+        let mut lines = meta.lines();
+        let mut next = |what: &str| lines.next().ok_or_else(|| anyhow!("no {what}"));
+        let mut line = next("timestamp")?;
+        if let Some(version) = line.strip_prefix("schema_version ") {
+            let major = version.split('.').next().unwrap_or_default();
+            if major != "2" {
+                return Err(anyhow!(
+                    "unsupported backup meta file schema version {version}"
+                ));
+            }
+            line = next("timestamp")?;
+        }
+        let timestamp = line.parse().context("invalid timestamp")?;
+        next("sequence number")?;
+        let mut line = next("number of files")?;
+        if line.starts_with("metadata ") {
+            line = next("number of files")?;
+        }
+        let count: usize = line.parse().context("invalid number of files")?;
+        let mut files = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut fields = next("file")?.split(' ');
+            let name = fields.next().unwrap_or_default();
+            let mut checksum = None;
+            while let (Some(field), Some(value)) = (fields.next(), fields.next()) {
+                if field == "crc32" {
+                    checksum = value.parse::<u32>().ok();
+                }
+            }
+            let checksum = checksum.ok_or_else(|| anyhow!("no checksum for {name}"))?;
+            files.push((name.to_string(), checksum));
+        }
+        Ok(Self { timestamp, files })
+    }
+
+    /// Adds up the sizes of the files of the backup in the backup engine
+    /// directory `path`.
+    pub(crate) fn size(&self, path: &Path) -> Result<u64> {
+        self.files.iter().try_fold(0, |size, (name, _)| {
+            let metadata =
+                fs::metadata(path.join(name)).with_context(|| format!("cannot read {name}"))?;
+            Ok(size + metadata.len())
+        })
+    }
+
+    /// Checks the files of the backup in the backup engine directory `path`
+    /// against their recorded checksums.
+    pub(crate) fn verify(&self, path: &Path) -> Result<()> {
+        for (name, expected) in &self.files {
+            let actual = crc32c(&path.join(name)).with_context(|| format!("cannot read {name}"))?;
+            if actual != *expected {
+                return Err(anyhow!(
+                    "{name} has checksum {actual}, but {expected} was recorded"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Computes the CRC32C checksum of the contents of `path`, which is what
+/// backup meta files record.
+fn crc32c(path: &Path) -> Result<u32> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0; 1 << 16];
+    let mut crc = !0;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(!crc);
+        }
+        crc = crc32c_extend(crc, &buf[..n]);
+    }
+}
+
+fn crc32c_extend(mut crc: u32, bytes: &[u8]) -> u32 {
+    /// The remainders of each byte by the Castagnoli polynomial, reflected.
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0x82f6_3b78
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    for &byte in bytes {
+        crc = TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::OpenOptions, io::Write};
+
+    use rocksdb::{
+        backup::{BackupEngine, BackupEngineOptions},
+        Env, DB,
+    };
+
+    use super::{crc32c_extend, BackupMeta};
+
+    #[test]
+    fn crc32c_check_value() {
+        assert_eq!(!crc32c_extend(!0, b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn meta_files() {
+        let meta = "1700000000\n42\nmetadata 6869\n2\n\
+                    private/1/MANIFEST-000005 crc32 1234\n\
+                    shared_checksum/000007_2894567812_590.sst crc32 2894567812\n";
+        let meta = BackupMeta::parse(meta).unwrap();
+        assert_eq!(meta.timestamp, 1_700_000_000);
+        assert_eq!(
+            meta.files,
+            [
+                ("private/1/MANIFEST-000005".to_string(), 1234),
+                (
+                    "shared_checksum/000007_2894567812_590.sst".to_string(),
+                    2_894_567_812
+                ),
+            ]
+        );
+        assert!(BackupMeta::parse("1700000000\n42\n1\nprivate/1/CURRENT\n").is_err());
+    }
+
+    #[test]
+    fn meta_schema_versions() {
+        let meta = "schema_version 2.1\n1700000000\n42\n1\n\
+                    private/1/CURRENT crc32 1234 size 16\n";
+        let meta = BackupMeta::parse(meta).unwrap();
+        assert_eq!(meta.files, [("private/1/CURRENT".to_string(), 1234)]);
+
+        let e = BackupMeta::parse("schema_version 3\n1700000000\n42\n0\n").unwrap_err();
+        assert!(e.to_string().contains("schema version 3"));
+    }
+
+    /// Reads and verifies a backup RocksDB's backup engine made.
+    #[test]
+    fn backup_engine_meta_files() {
+        let data = tempfile::tempdir().unwrap();
+        let backup = tempfile::tempdir().unwrap();
+        let db = DB::open_default(data.path()).unwrap();
+        db.put(b"key", b"value").unwrap();
+        let mut engine = BackupEngine::open(
+            &BackupEngineOptions::new(backup.path()).unwrap(),
+            &Env::new().unwrap(),
+        )
+        .unwrap();
+        engine.create_new_backup_flush(&db, true).unwrap();
+        let info = &engine.get_backup_info()[0];
+
+        let meta = BackupMeta::read(backup.path(), info.backup_id).unwrap();
+        assert_eq!(meta.timestamp, info.timestamp);
+        assert_eq!(meta.files.len(), info.num_files as usize);
+        assert_eq!(meta.size(backup.path()).unwrap(), info.size);
+        meta.verify(backup.path()).unwrap();
+
+        let (name, _) = &meta.files[0];
+        OpenOptions::new()
+            .append(true)
+            .open(backup.path().join(name))
+            .unwrap()
+            .write_all(b"corrupt")
+            .unwrap();
+        assert!(meta.verify(backup.path()).is_err());
+    }
+}
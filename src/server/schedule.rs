@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use dioxus_logger::tracing;
+
+use super::State;
+use crate::config::{BackupSchedule, TimeWindow};
+
+/// The last scheduled backup and when the next one is due.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ScheduleStatus {
+    pub(crate) interval: Duration,
+    pub(crate) keep: u32,
+    pub(crate) window: Option<TimeWindow>,
+    pub(crate) last_run: Option<(DateTime<Utc>, Result<(), String>)>,
+    pub(crate) next_run: Option<DateTime<Utc>>,
+}
+
+/// Returns the first time at or after `time` that falls in `window`.
+fn next_in(window: Option<TimeWindow>, time: DateTime<Utc>) -> DateTime<Utc> {
+    let Some(window) = window else {
+        return time;
+    };
+    if window.contains(time.time()) {
+        return time;
+    }
+    let start = time.date_naive().and_time(window.start).and_utc();
+    if start > time {
+        start
+    } else {
+        start
+            .checked_add_signed(TimeDelta::days(1))
+            .unwrap_or(start)
+    }
+}
+
+impl State {
+    /// Backs up the store as `schedule` says until the store is closed.
+    pub async fn back_up_on(self, schedule: BackupSchedule) {
+        let interval = Duration::from_secs(schedule.interval);
+        let delay = TimeDelta::from_std(interval).unwrap_or(TimeDelta::MAX);
+        self.update_schedule(|status| {
+            *status = ScheduleStatus {
+                interval,
+                keep: schedule.keep,
+                window: schedule.window,
+                ..ScheduleStatus::default()
+            };
+        });
+        loop {
+            let due = Utc::now()
+                .checked_add_signed(delay)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            let next = next_in(schedule.window, due);
+            self.update_schedule(|status| status.next_run = Some(next));
+            let wait = (next - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            if !self.is_open().await {
+                break;
+            }
+            let result = self.create_backup(schedule.keep).await;
+            if let Err(e) = &result {
+                tracing::error!("scheduled backup failed: {e:#}");
+            } else {
+                tracing::info!("scheduled backup of {} done", self.data_dir().display());
+            }
+            let last = (Utc::now(), result.map_err(|e| format!("{e:#}")));
+            self.update_schedule(|status| status.last_run = Some(last));
+        }
+        self.update_schedule(|status| status.next_run = None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveTime, Utc};
+
+    use super::next_in;
+    use crate::config::TimeWindow;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn window(start: &str, end: &str) -> Option<TimeWindow> {
+        let parse = |t| NaiveTime::parse_from_str(t, "%H:%M").unwrap();
+        Some(TimeWindow {
+            start: parse(start),
+            end: parse(end),
+        })
+    }
+
+    #[test]
+    fn no_window() {
+        let time = at("2024-06-01T12:34:56Z");
+        assert_eq!(next_in(None, time), time);
+    }
+
+    #[test]
+    fn within_a_day() {
+        let night = window("01:00", "05:00");
+        let inside = at("2024-06-01T03:00:00Z");
+        assert_eq!(next_in(night, inside), inside);
+        assert_eq!(
+            next_in(night, at("2024-06-01T00:30:00Z")),
+            at("2024-06-01T01:00:00Z")
+        );
+        assert_eq!(
+            next_in(night, at("2024-06-01T05:00:00Z")),
+            at("2024-06-02T01:00:00Z")
+        );
+        assert_eq!(
+            next_in(night, at("2024-06-30T12:00:00Z")),
+            at("2024-07-01T01:00:00Z")
+        );
+    }
+
+    #[test]
+    fn across_midnight() {
+        let night = window("22:00", "02:00");
+        let late = at("2024-06-01T23:00:00Z");
+        assert_eq!(next_in(night, late), late);
+        let early = at("2024-06-02T01:00:00Z");
+        assert_eq!(next_in(night, early), early);
+        assert_eq!(
+            next_in(night, at("2024-06-01T02:00:00Z")),
+            at("2024-06-01T22:00:00Z")
+        );
+        assert_eq!(
+            next_in(night, at("2024-06-01T12:00:00Z")),
+            at("2024-06-01T22:00:00Z")
+        );
+    }
+}
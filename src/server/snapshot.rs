@@ -0,0 +1,88 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use review_database::Store;
+use tempfile::TempDir;
+
+use super::rocks;
+
+/// A data directory a backup is restored into so it can be browsed. The
+/// directory is deleted when the snapshot is dropped.
+///
+/// The backup carries no record of the format version it was written in, so
+/// the data directory has no `VERSION` file.
+pub(crate) struct Snapshot {
+    dir: TempDir,
+}
+
+impl Snapshot {
+    /// Restores backup `id` of the backup directory `backup` into a new
+    /// directory under `scratch`, or under the system's temporary directory
+    /// if `scratch` is `None`. The copy has no backups of its own.
+    ///
+    /// The files of the backup are first linked, or copied, into a backup
+    /// directory of the copy's own, and review-database restores the backup
+    /// from there, so no backup engine is ever opened on `backup`.
+    pub(crate) fn from_backup(backup: &Path, id: u32, scratch: Option<&Path>) -> Result<Self> {
+        let snapshot = Self::empty(scratch)?;
+        fs::create_dir_all(snapshot.data_dir())?;
+        fs::create_dir_all(snapshot.backup_dir())?;
+        let staged = snapshot.dir.path().join("staged");
+        stage_backup(&backup.join(rocks::DB_DIR), id, &staged.join(rocks::DB_DIR))
+            .with_context(|| format!("cannot read backup {id}"))?;
+        let mut store = Store::new(&snapshot.data_dir(), &staged)?;
+        store
+            .restore_from_backup(id)
+            .with_context(|| format!("cannot restore backup {id}"))?;
+        drop(store);
+        fs::remove_dir_all(&staged)
+            .with_context(|| format!("cannot remove {}", staged.display()))?;
+        Ok(snapshot)
+    }
+
+    fn empty(scratch: Option<&Path>) -> Result<Self> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("deview-");
+        let dir = match scratch {
+            Some(scratch) => builder.tempdir_in(scratch),
+            None => builder.tempdir(),
+        }
+        .context("cannot create a scratch directory")?;
+        Ok(Self { dir })
+    }
+
+    pub(crate) fn data_dir(&self) -> PathBuf {
+        self.dir.path().join("data")
+    }
+
+    pub(crate) fn backup_dir(&self) -> PathBuf {
+        self.dir.path().join("backup")
+    }
+}
+
+/// Links, or copies where it cannot link, the meta file and the files of
+/// backup `id` of the backup engine directory `from` into the backup engine
+/// directory `to`.
+fn stage_backup(from: &Path, id: u32, to: &Path) -> Result<()> {
+    let meta = rocks::BackupMeta::read(from, id)?;
+    let names = meta
+        .files
+        .iter()
+        .map(|(name, _)| PathBuf::from(name))
+        .chain([Path::new("meta").join(id.to_string())]);
+    for name in names {
+        let (src, dst) = (from.join(&name), to.join(&name));
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Backup files are never modified once written, so a link is as good
+        // as a copy.
+        fs::hard_link(&src, &dst)
+            .or_else(|_| fs::copy(&src, &dst).map(|_| ()))
+            .with_context(|| format!("cannot copy {}", src.display()))?;
+    }
+    Ok(())
+}
@@ -1,198 +1,727 @@
 #[cfg(feature = "server")]
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "server")]
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+#[cfg(feature = "server")]
+use chrono::{DateTime, Utc};
 use dioxus::prelude::*;
 #[cfg(feature = "server")]
 use review_database::{migrate_data_dir, Store};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "server")]
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+#[cfg(feature = "server")]
+use super::{
+    rocks::{self, ColumnFamilyStats},
+    schedule::ScheduleStatus,
+    snapshot::Snapshot,
+    tables::{Data, Source},
+};
 
 #[cfg(feature = "server")]
 #[derive(Clone)]
 pub struct State {
-    pub(crate) store: Arc<RwLock<Store>>,
-    version: String,
+    store: Arc<RwLock<Option<Opened>>>,
+    data_dir: PathBuf,
+    backup_dir: PathBuf,
+    /// Set for a state that never writes to its directories.
+    read_only: Option<ReadOnly>,
+    /// Where secondary instances keep their log files, if not in the
+    /// system's temporary directory.
+    scratch: Option<PathBuf>,
+    /// When a table was last read successfully, for the readiness check.
+    last_read: Arc<std::sync::Mutex<Option<DateTime<Utc>>>>,
+    /// When the secondary instance last read what has been written since.
+    caught_up_at: Arc<std::sync::Mutex<Option<DateTime<Utc>>>>,
+    /// Set while backups are scheduled.
+    schedule: Arc<std::sync::Mutex<Option<ScheduleStatus>>>,
+}
+
+/// An open data directory.
+#[cfg(feature = "server")]
+struct Opened {
+    /// The store, unless the state is read-only.
+    store: Option<Store>,
+    /// The secondary instance of the database. A read-only state reads
+    /// everything through it; a writable state opens it on first use, for
+    /// the column family browser and statistics only.
+    db: OnceLock<rocks::Db>,
+    /// The scratch directory a browsed backup was restored into. Declared
+    /// after `db` so the database is closed before the directory is deleted.
+    snapshot: Option<Snapshot>,
+}
+
+#[cfg(feature = "server")]
+impl Opened {
+    fn new(store: Store) -> Self {
+        Self {
+            store: Some(store),
+            db: OnceLock::new(),
+            snapshot: None,
+        }
+    }
+
+    /// Opens the database in `data_dir` as a secondary instance only, which
+    /// never writes to the directory, keeping its log files under `scratch`.
+    fn read_only(
+        data_dir: &Path,
+        scratch: Option<&Path>,
+        snapshot: Option<Snapshot>,
+    ) -> Result<Self> {
+        let db = rocks::Db::open(&data_dir.join(rocks::DB_DIR), scratch)?;
+        Ok(Self {
+            store: None,
+            db: OnceLock::from(db),
+            snapshot,
+        })
+    }
+
+    /// Returns the secondary instance of the database in `data_dir`, opening
+    /// it on first use with its log files under `scratch`.
+    fn db(&self, data_dir: &Path, scratch: Option<&Path>) -> Result<&rocks::Db> {
+        if let Some(db) = self.db.get() {
+            return Ok(db);
+        }
+        let db = rocks::Db::open(&data_dir.join(rocks::DB_DIR), scratch)?;
+        Ok(self.db.get_or_init(|| db))
+    }
+}
+
+/// Runs `f` on a thread where blocking is allowed, as RocksDB's file I/O
+/// requires, and returns its result.
+#[cfg(feature = "server")]
+pub(super) async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(f).await?
+}
+
+#[cfg(feature = "server")]
+#[derive(Clone)]
+struct ReadOnly {
+    /// The backup the data directory was restored from, if it is one being
+    /// browsed.
+    backup: Option<u32>,
+}
+
+/// How [`State::open`] opens a data directory.
+#[cfg(feature = "server")]
+#[derive(Clone, Debug, Default)]
+pub struct OpenOptions {
+    /// Migrates the data directory before opening it.
+    pub migrate: bool,
+    /// Opens the data directory read-only; see [`State::open_read_only`].
+    pub read_only: bool,
+    /// Where backups are restored for browsing and secondary instances keep
+    /// their log files.
+    pub scratch: Option<PathBuf>,
 }
 
 #[cfg(feature = "server")]
 impl State {
-    pub fn new<R: AsRef<Path>>(data: R, backup: R) -> Result<Self> {
-        migrate_data_dir(data.as_ref(), backup.as_ref())?;
-        let store = Arc::new(RwLock::new(Store::new(data.as_ref(), backup.as_ref())?));
-        let version = data.as_ref().join("VERSION");
-        let version = Self::read_version_file(version.as_path())?;
-        Ok(Self { store, version })
+    /// Opens the store in `data`, first migrating it to the version this
+    /// build expects if `migrate` is set.
+    pub fn new<R: AsRef<Path>>(data: R, backup: R, migrate: bool) -> Result<Self> {
+        if migrate {
+            migrate_data_dir(data.as_ref(), backup.as_ref())?;
+        }
+        let store = Store::new(data.as_ref(), backup.as_ref())?;
+        let state = Self {
+            store: Arc::new(RwLock::new(Some(Opened::new(store)))),
+            data_dir: data.as_ref().to_path_buf(),
+            backup_dir: backup.as_ref().to_path_buf(),
+            read_only: None,
+            scratch: None,
+            last_read: Arc::default(),
+            caught_up_at: Arc::default(),
+            schedule: Arc::default(),
+        };
+        state.version()?;
+        Ok(state)
+    }
+
+    /// Opens the data directory `data` without ever writing to `data` or
+    /// `backup`. The secondary instance keeps its log files under `scratch`.
+    ///
+    /// review-database cannot open a store read-only, so no store is opened:
+    /// the database is read through a RocksDB secondary instance, backups
+    /// through their meta files, and every mutating operation is refused.
+    /// This also works while REview is running on `data`; reads show the
+    /// data as of the last [`State::catch_up`].
+    pub fn open_read_only<R: AsRef<Path>>(
+        data: R,
+        backup: R,
+        scratch: Option<&Path>,
+    ) -> Result<Self> {
+        let opened = Opened::read_only(data.as_ref(), scratch, None)?;
+        let state = Self {
+            store: Arc::new(RwLock::new(Some(opened))),
+            data_dir: data.as_ref().to_path_buf(),
+            backup_dir: backup.as_ref().to_path_buf(),
+            read_only: Some(ReadOnly { backup: None }),
+            scratch: scratch.map(Path::to_path_buf),
+            last_read: Arc::default(),
+            caught_up_at: Arc::new(std::sync::Mutex::new(Some(Utc::now()))),
+            schedule: Arc::default(),
+        };
+        state.version()?;
+        Ok(state)
+    }
+
+    /// Opens the store in `data` as `options` says.
+    pub fn open<R: AsRef<Path>>(data: R, backup: R, options: &OpenOptions) -> Result<Self> {
+        if options.read_only {
+            return Self::open_read_only(data, backup, options.scratch.as_deref());
+        }
+        let mut state = Self::new(data, backup, options.migrate)?;
+        state.scratch.clone_from(&options.scratch);
+        Ok(state)
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.is_some()
+    }
+
+    /// Returns the ID of the backup the store was restored from, if it was
+    /// opened by [`State::browse_backup`].
+    pub fn browsed_backup(&self) -> Option<u32> {
+        self.read_only.as_ref().and_then(|r| r.backup)
     }
 
-    pub fn version(&self) -> &str {
-        &self.version
+    /// Fails if the state was opened read-only. Every operation that modifies
+    /// the store checks this first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in read-only mode.
+    pub(crate) fn check_writable(&self) -> Result<()> {
+        if self.is_read_only() {
+            Err(anyhow!("Deview is in read-only mode"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Locks the store for reading.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store is closed, e.g. during a migration, or
+    /// the state is read-only.
+    pub(crate) async fn store(&self) -> Result<RwLockReadGuard<'_, Store>> {
+        self.check_writable()?;
+        let began = Instant::now();
+        let opened = self.store.read().await;
+        super::metrics::record_lock_wait("read", began.elapsed());
+        RwLockReadGuard::try_map(opened, |opened| opened.as_ref()?.store.as_ref())
+            .map_err(|_| anyhow!("the store is closed"))
+    }
+
+    /// Calls `f` on a blocking thread with the store locked for exclusive
+    /// access, as operations that replace the database files require.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store is closed or the state is read-only.
+    pub(crate) async fn with_store_mut<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Store) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        self.check_writable()?;
+        let began = Instant::now();
+        let mut opened = self.store.clone().write_owned().await;
+        super::metrics::record_lock_wait("write", began.elapsed());
+        blocking(move || {
+            let store = opened
+                .as_mut()
+                .and_then(|opened| opened.store.as_mut())
+                .ok_or_else(|| anyhow!("the store is closed"))?;
+            f(store)
+        })
+        .await
+    }
+
+    pub async fn is_open(&self) -> bool {
+        self.store.read().await.is_some()
+    }
+
+    /// Records that a table was just read successfully.
+    pub(crate) fn mark_read(&self) {
+        if let Ok(mut last_read) = self.last_read.lock() {
+            *last_read = Some(Utc::now());
+        }
+    }
+
+    /// Returns when a table was last read successfully.
+    pub fn last_read(&self) -> Option<DateTime<Utc>> {
+        self.last_read.lock().ok().and_then(|t| *t)
+    }
+
+    /// Records a change in the status of the backup schedule.
+    pub(crate) fn update_schedule(&self, f: impl FnOnce(&mut ScheduleStatus)) {
+        if let Ok(mut schedule) = self.schedule.lock() {
+            f(schedule.get_or_insert_with(ScheduleStatus::default));
+        }
+    }
+
+    /// Returns the status of the backup schedule, if backups are scheduled.
+    pub(crate) fn schedule(&self) -> Option<ScheduleStatus> {
+        self.schedule
+            .lock()
+            .ok()
+            .and_then(|schedule| schedule.clone())
+    }
+
+    /// Closes the store. Dropping it lets RocksDB sync its write-ahead log and
+    /// release the database lock.
+    pub async fn close(&self) {
+        drop(self.store.write().await.take());
+    }
+
+    /// Returns when the secondary instance last caught up with the data
+    /// directory.
+    pub fn caught_up_at(&self) -> Option<DateTime<Utc>> {
+        self.caught_up_at.lock().ok().and_then(|t| *t)
+    }
+
+    /// Checks whether the secondary instance caught up less than `period`
+    /// ago.
+    fn caught_up_within(&self, period: Duration) -> bool {
+        self.caught_up_at()
+            .and_then(|at| (Utc::now() - at).to_std().ok())
+            .is_some_and(|since| since < period)
+    }
+
+    /// Reads whatever REview has written to the data directory of a
+    /// read-only state since it last caught up. A writable state reads its
+    /// tables from the store instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state is not read-only, is a browsed backup,
+    /// or cannot catch up; the data stays as it was then.
+    pub async fn catch_up(&self) -> Result<()> {
+        let read_only = self
+            .read_only
+            .as_ref()
+            .ok_or_else(|| anyhow!("only a read-only store can catch up"))?;
+        if read_only.backup.is_some() {
+            return Err(anyhow!("a backup cannot catch up"));
+        }
+        self.with_db(rocks::Db::catch_up).await?;
+        if let Ok(mut caught_up_at) = self.caught_up_at.lock() {
+            *caught_up_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    /// Catches up as [`State::catch_up`] does, unless the state caught up
+    /// less than `cooldown` ago.
+    pub(crate) async fn catch_up_after(&self, cooldown: Duration) -> Result<()> {
+        if self.caught_up_within(cooldown) {
+            return Err(anyhow!(
+                "caught up less than {} seconds ago; try again later",
+                cooldown.as_secs()
+            ));
+        }
+        self.catch_up().await
+    }
+
+    /// Catches up every `interval` until the store is closed.
+    pub async fn catch_up_every(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if !self.is_open().await {
+                break;
+            }
+            if let Err(e) = self.catch_up().await {
+                dioxus_logger::tracing::error!("failed to catch up: {e:#}");
+            }
+        }
+    }
+
+    /// Returns the version recorded in the data directory's `VERSION` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for a browsed backup, whose version is unknown.
+    pub fn version(&self) -> Result<String> {
+        if let Some(id) = self.browsed_backup() {
+            return Err(anyhow!("the format version of backup {id} is unknown"));
+        }
+        Self::read_version(&self.data_dir)
     }
 
-    fn read_version_file(path: &Path) -> Result<String> {
+    /// Reads the `VERSION` file in `data_dir` without opening the store.
+    pub(crate) fn read_version(data_dir: &Path) -> Result<String> {
         use std::fs::File;
         use std::io::Read;
 
         let mut ver = String::new();
-        File::open(path)
+        File::open(data_dir.join("VERSION"))
             .context("cannot open VERSION")?
             .read_to_string(&mut ver)
             .context("cannot read VERSION")?;
-        Ok(ver)
+        Ok(ver.trim().to_string())
     }
 
-    pub async fn backups(&self) -> Result<Vec<BackupEngineInfoProps>> {
-        let store = self.store.read().await;
-        Ok(store
-            .get_backup_info()?
-            .into_iter()
-            .map(From::from)
-            .collect())
+    /// Closes the store, migrates the data directory and opens it again.
+    ///
+    /// The store is reopened even if the migration fails, so Deview keeps
+    /// showing the data as it was.
+    pub async fn migrate(&self) -> Result<()> {
+        self.check_writable()?;
+        let mut opened = self.store.clone().write_owned().await;
+        let state = self.clone();
+        blocking(move || {
+            drop(opened.take());
+            let migrated = migrate_data_dir(&state.data_dir, &state.backup_dir);
+            state.reopen(&mut opened, migrated)
+        })
+        .await
     }
-}
-
-#[cfg(feature = "server")]
-pub(crate) async fn review() -> Result<State, ServerFnError> {
-    use axum::Extension;
-    let Extension(review): Extension<State> = extract().await?;
-    Ok(review)
-}
 
-#[component]
-pub fn BackupDigest() -> Element {
-    let entries = use_server_future(backups)?;
-    rsx! {
-        tr {
-            th { style: "width: 200px; text-align: right;", scope: "row",
-                Link {
-                    class: "hover:bg-gray-100",
-                    to: crate::Route::Table {
-                        name: super::tables::LookUp::Backup.to_string(),
-                    },
-                    "Backups"
-                }
+    /// Opens the store into `opened` after `done` has changed its files,
+    /// returning the error of `done` and of opening the store, if any. The
+    /// store is left closed only if it cannot be opened at all.
+    fn reopen(&self, opened: &mut Option<Opened>, done: Result<()>) -> Result<()> {
+        match Store::new(&self.data_dir, &self.backup_dir) {
+            Ok(store) => {
+                *opened = Some(Opened::new(store));
+                done
             }
-            match entries() {
-                None => rsx!{td { colspan: 2, "Loading..." }},
-                Some(Err(e)) => rsx!{td {colspan: 2, "{e}"}},
-                Some(Ok(entries)) => rsx!{
-                    td { style: "width: 100px; text-align: center;", "{entries.len()}" }
-                    td {
-                        ol {
-                            for entry in entries.into_iter().take(3) {
-                                li {
-                                    Backup { entry: entry }
-                                }
-                            }
-                        }
-                    }
-                }
+            Err(e) => {
+                *opened = None;
+                Err(match done {
+                    Ok(()) => e.context("cannot reopen the store"),
+                    Err(done) => anyhow!("{done:#}; then cannot reopen the store: {e:#}"),
+                })
             }
         }
     }
-}
 
-#[component]
-fn Columns() -> Element {
-    let columns = &["ID", "Size", "Number of files", "Time"];
-    rsx! {
-        tr {
-            for col in columns {
-                th { scope: "col", "{col}" }
+    /// Backs up the store from a fresh checkpoint and purges all but the
+    /// newest `keep` backups.
+    pub async fn create_backup(&self, keep: u32) -> Result<()> {
+        self.check_writable()?;
+        self.with_store_mut(move |store| store.backup(true, keep))
+            .await
+    }
+
+    /// Replaces the contents of the store with backup `id`, then reopens the
+    /// store so nothing is served from before the restore.
+    ///
+    /// The store is reopened even if the restore fails, as after a
+    /// migration.
+    pub async fn restore_backup(&self, id: u32) -> Result<()> {
+        self.check_writable()?;
+        let mut opened = self.store.clone().write_owned().await;
+        let state = self.clone();
+        blocking(move || {
+            let restored = opened
+                .as_mut()
+                .and_then(|opened| opened.store.as_mut())
+                .ok_or_else(|| anyhow!("the store is closed"))?
+                .restore_from_backup(id);
+            drop(opened.take());
+            state.reopen(&mut opened, restored)
+        })
+        .await
+    }
+
+    /// Deletes all but the newest `keep` backups.
+    pub async fn purge_backups(&self, keep: u32) -> Result<()> {
+        self.check_writable()?;
+        self.with_store_mut(move |store| store.purge_old_backups(keep))
+            .await
+    }
+
+    /// Checks the files of every backup against the checksums recorded in
+    /// its meta file, returning the result for each backup ID.
+    pub async fn verify_backups(&self) -> Result<Vec<(u32, Result<()>)>> {
+        let dir = self.backup_dir.join(rocks::DB_DIR);
+        let ids = rocks::backup_ids(&dir)?;
+        let mut verified = Vec::with_capacity(ids.len());
+        for id in ids {
+            // Creating and purging backups take the write lock, so the backup
+            // stays while it is checked, and only while it is checked.
+            let opened = self.store.clone().read_owned().await;
+            if opened.is_none() {
+                return Err(anyhow!("the store is closed"));
             }
+            let dir = dir.clone();
+            let res = blocking(move || {
+                let _opened = opened;
+                Ok(rocks::BackupMeta::read(&dir, id).and_then(|meta| meta.verify(&dir)))
+            })
+            .await?;
+            verified.push((id, res));
         }
+        Ok(verified)
     }
-}
 
-#[component]
-fn Row(entry: BackupEngineInfoProps) -> Element {
-    rsx! {
-        tr {
-            td { "{entry.backup_id}" }
-            td { "{entry.size}" }
-            td { "{entry.num_files}" }
-            td { "{entry.timestamp}" }
+    /// Restores backup `id` into a scratch directory under `scratch` and
+    /// opens it as a separate read-only state. Closing that state deletes
+    /// the scratch directory.
+    ///
+    /// The state has no format version, which the backup does not record.
+    pub async fn browse_backup(&self, id: u32, scratch: Option<&Path>) -> Result<Self> {
+        // Purging takes the write lock, so the backup stays meanwhile.
+        let opened = self.store.clone().read_owned().await;
+        if opened.is_none() {
+            return Err(anyhow!("the store is closed"));
         }
+        let backup_dir = self.backup_dir.clone();
+        let scratch = scratch.map(Path::to_path_buf);
+        let dir = scratch.clone();
+        let opened = blocking(move || {
+            let snapshot = Snapshot::from_backup(&backup_dir, id, dir.as_deref())?;
+            drop(opened);
+            Opened::read_only(&snapshot.data_dir(), dir.as_deref(), Some(snapshot))
+        })
+        .await?;
+        let snapshot = opened
+            .snapshot
+            .as_ref()
+            .ok_or_else(|| anyhow!("backup {id} was not restored"))?;
+        let (data_dir, backup_dir) = (snapshot.data_dir(), snapshot.backup_dir());
+        Ok(Self {
+            store: Arc::new(RwLock::new(Some(opened))),
+            data_dir,
+            backup_dir,
+            read_only: Some(ReadOnly { backup: Some(id) }),
+            scratch,
+            last_read: Arc::default(),
+            caught_up_at: Arc::default(),
+            schedule: Arc::default(),
+        })
     }
-}
 
-#[component]
-pub(crate) fn Full() -> Element {
-    let entries = use_server_future(backups)?;
-    let title = super::tables::LookUp::Backup.to_string();
-    rsx! {
-        table { style: "table-layout: fixed;
-                max-width: 1200px; max-height: 1200px;
-                overflow: auto; display: block;
-                border-spacing: 0;",
-            caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
-                "{title}"
+    /// Calls `f` on a blocking thread with the secondary instance of the
+    /// RocksDB database in the data directory, keeping the store, or the
+    /// scratch directory of a browsed backup, in place meanwhile. The
+    /// instance is opened once per store.
+    ///
+    /// A writable state reads its tables from the store, and the instance
+    /// only for the column family browser and statistics, so it catches up
+    /// there at most every [`CATCH_UP_COOLDOWN`]. A read-only state shows the
+    /// data as of its last [`State::catch_up`].
+    pub(super) async fn with_db<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&rocks::Db) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let opened = self.store.clone().read_owned().await;
+        let (data_dir, scratch) = (self.data_dir.clone(), self.scratch.clone());
+        let catch_up = !self.is_read_only() && !self.caught_up_within(CATCH_UP_COOLDOWN);
+        let res = blocking(move || {
+            let opened = opened
+                .as_ref()
+                .ok_or_else(|| anyhow!("the store is closed"))?;
+            let db = opened.db(&data_dir, scratch.as_deref())?;
+            if catch_up {
+                db.catch_up()?;
             }
-            thead {
-                match entries() {
-                    None => rsx!{td {colspan: 2, "Loading..."}},
-                    Some(Err(e)) => rsx!{td {colspan: 2, "{e}"}},
-                    Some(Ok(entries)) => {
-                        rsx!{
-                            Columns{}
-                            for entry in entries.into_iter() {
-                                Row { entry }
-                            }
-                        }
-                    }
-                }
+            f(db)
+        })
+        .await;
+        if catch_up && res.is_ok() {
+            if let Ok(mut caught_up_at) = self.caught_up_at.lock() {
+                *caught_up_at = Some(Utc::now());
             }
         }
+        res
+    }
+
+    /// Calls `f` on a blocking thread with what tables are read from: the
+    /// store of a writable state, or the secondary instance of a read-only
+    /// one, which this does not catch up.
+    pub(super) async fn with_source<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Source) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let opened = self.store.clone().read_owned().await;
+        let backup_dir = self.backup_dir.clone();
+        blocking(move || {
+            let opened = opened
+                .as_ref()
+                .ok_or_else(|| anyhow!("the store is closed"))?;
+            let data = match (&opened.store, opened.db.get()) {
+                (Some(store), _) => Data::Store(store),
+                (None, Some(db)) => Data::Secondary(db),
+                (None, None) => return Err(anyhow!("the store is closed")),
+            };
+            f(&Source {
+                data,
+                backup_dir: &backup_dir,
+            })
+        })
+        .await
+    }
+
+    /// Reads RocksDB's statistics of the column families behind the store.
+    pub(crate) async fn column_family_stats(&self) -> Result<Vec<ColumnFamilyStats>> {
+        self.with_db(rocks::Db::column_family_stats).await
     }
 }
 
-#[component]
-fn Backup(entry: BackupEngineInfoProps) -> Element {
-    rsx! {
-        div {
-            p {
-                "id {entry.backup_id}: size {entry.size}, containing {entry.num_files} files at {entry.timestamp};"
-            }
-        }
+/// Returns the state of `profile` for the signed-in user, opening it if it
+/// is not open yet.
+#[cfg(feature = "server")]
+pub(crate) async fn review(profile: &str) -> Result<State, ServerFnError> {
+    use axum::Extension;
+
+    super::auth::current_user().await?;
+    let Extension(profiles): Extension<super::Profiles> = extract().await?;
+    profiles.get(profile).await.map_err(ServerFnError::new)
+}
+
+/// How current the data a read-only store shows is.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ReadOnlyStatus {
+    /// When the store last caught up with REview.
+    caught_up_at: Option<String>,
+    /// The ID of the backup being browsed.
+    backup: Option<u32>,
+}
+
+#[server]
+async fn read_only(profile: String) -> Result<Option<ReadOnlyStatus>, ServerFnError> {
+    let review = review(&profile).await?;
+    if !review.is_read_only() {
+        return Ok(None);
     }
+    Ok(Some(ReadOnlyStatus {
+        caught_up_at: review.caught_up_at().map(super::tables::format_time),
+        backup: review.browsed_backup(),
+    }))
 }
 
+/// How long after catching up the banner's "Catch up" is refused, so that
+/// repeated requests cannot keep RocksDB replaying the primary's log.
+#[cfg(feature = "server")]
+const CATCH_UP_COOLDOWN: Duration = Duration::from_secs(10);
+
 #[server]
-async fn backups() -> Result<Vec<BackupEngineInfoProps>, ServerFnError> {
-    let review = review().await?;
+async fn catch_up(profile: String) -> Result<(), ServerFnError> {
+    use super::{
+        auth::authorize,
+        permission::{Access, ADMINISTRATORS},
+    };
 
-    review.backups().await.map_err(ServerFnError::new)
+    authorize(ADMINISTRATORS, Access::Read).await?;
+    let review = review(&profile).await?;
+    review
+        .catch_up_after(CATCH_UP_COOLDOWN)
+        .await
+        .map_err(ServerFnError::new)
 }
 
-#[derive(PartialEq, Props, Clone, Serialize, Deserialize)]
-pub struct BackupEngineInfoProps {
-    /// Timestamp of the backup
-    pub timestamp: i64,
-    /// ID of the backup
-    pub backup_id: u32,
-    /// Size of the backup
-    pub size: u64,
-    /// Number of files related to the backup
-    pub num_files: u32,
+/// Closes a browsed backup, deleting its scratch directory.
+#[server]
+async fn close_backup(profile: String) -> Result<(), ServerFnError> {
+    use axum::Extension;
+
+    use super::{
+        auth::authorize,
+        permission::{Access, ADMINISTRATORS},
+    };
+
+    let user = authorize(ADMINISTRATORS, Access::Read).await?;
+    let review = review(&profile).await?;
+    if review.browsed_backup().is_none() {
+        return Err(ServerFnError::new(format!("{profile} is not a backup")));
+    }
+    let Extension(profiles): Extension<super::Profiles> = extract().await?;
+    dioxus_logger::tracing::info!("{} closes {profile}", user.username);
+    profiles.close(&profile).await.map_err(ServerFnError::new)
 }
 
-#[cfg(feature = "server")]
-impl From<review_database::BackupEngineInfo> for BackupEngineInfoProps {
-    fn from(input: review_database::BackupEngineInfo) -> Self {
-        Self {
-            timestamp: input.timestamp,
-            backup_id: input.backup_id,
-            size: input.size,
-            num_files: input.num_files,
+/// Warns on every page that changes are disabled, and lets the user catch up
+/// with REview, or close the backup being browsed.
+#[component]
+pub fn ReadOnlyBanner(profile: String) -> Element {
+    let read_only = use_server_future(use_reactive((&profile,), |(profile,)| read_only(profile)))?;
+    let navigator = use_navigator();
+    let mut error = use_signal(|| None::<String>);
+    let Some(Ok(Some(status))) = read_only() else {
+        return rsx! {};
+    };
+    if let Some(id) = status.backup {
+        let origin = super::profile::browsed_backup(&profile)
+            .map_or(super::DEFAULT_PROFILE, |(origin, _)| origin)
+            .to_string();
+        return rsx! {
+            div { style: "background-color: rgb(219, 234, 254); border-bottom: 1px solid steelblue;
+                    padding: 4px 8px; text-align: center;",
+                "Browsing backup {id} of profile {origin}, restored into a scratch directory; its format version is unknown. Changes are disabled. "
+                button {
+                    class: "hover:bg-gray-100",
+                    style: "border: 1px solid rgba(0, 0, 0, 0.5); padding: 0 4px;",
+                    onclick: move |_| {
+                        let profile = profile.clone();
+                        let origin = origin.clone();
+                        async move {
+                            match close_backup(profile).await {
+                                Ok(()) => {
+                                    navigator.push(crate::Route::Home { profile: origin });
+                                }
+                                Err(e) => error.set(Some(e.to_string())),
+                            }
+                        }
+                    },
+                    "Close"
+                }
+                if let Some(e) = error() {
+                    p { color: "red", "{e}" }
+                }
+            }
+        };
+    }
+    rsx! {
+        div { style: "background-color: rgb(254, 243, 199); border-bottom: 1px solid orange;
+                padding: 4px 8px; text-align: center;",
+            "Read-only mode: Deview is reading the data directory as a secondary instance and will not modify it."
+            if let Some(time) = status.caught_up_at {
+                " Last caught up at {time}. "
+            }
+            button {
+                class: "hover:bg-gray-100",
+                style: "border: 1px solid rgba(0, 0, 0, 0.5); padding: 0 4px;",
+                onclick: move |_| {
+                    let profile = profile.clone();
+                    async move {
+                        match catch_up(profile).await {
+                            Ok(()) => {
+                                eval("window.location.reload()");
+                            }
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                    }
+                },
+                "Catch up"
+            }
+            if let Some(e) = error() {
+                p { color: "red", "{e}" }
+            }
         }
     }
 }
 
 #[component]
-pub(crate) fn Digest() -> Element {
-    let version = use_server_future(version)?;
+pub(crate) fn Digest(profile: String) -> Element {
+    let version = use_server_future(use_reactive((&profile,), |(profile,)| version(profile)))?;
     rsx! {
         p {
             "version: "
@@ -205,7 +734,13 @@ pub(crate) fn Digest() -> Element {
 }
 
 #[server]
-async fn version() -> Result<String, ServerFnError> {
-    let review = review().await?;
-    Ok(review.version().to_string())
+async fn version(profile: String) -> Result<String, ServerFnError> {
+    use super::{
+        auth::authorize,
+        permission::{Access, EVERYONE},
+    };
+
+    authorize(EVERYONE, Access::Read).await?;
+    let review = review(&profile).await?;
+    review.version().map_err(ServerFnError::new)
 }
@@ -1,12 +1,579 @@
 mod access_token;
 mod account;
-use std::str::FromStr;
+mod backup;
+#[cfg(feature = "server")]
+use std::ops::ControlFlow;
+use std::{fmt, str::FromStr};
 
 use dioxus::prelude::*;
-use strum_macros::EnumString;
+#[cfg(feature = "server")]
+use review_database::{Direction, Store};
+use serde::{Deserialize, Serialize};
+
+use super::permission::{Access, Permission};
+#[cfg(feature = "server")]
+use super::rocks;
+
+/// Every table Deview shows, in the order of the navigation bar.
+static TABLES: &[&dyn AnyTable] = &[
+    &access_token::AccessTokens,
+    &account::Accounts,
+    &backup::Backups,
+];
+
+/// Describes a review-database table. Adding an implementation to
+/// [`TABLES`] adds the table to the overview, the navigation bar, the table
+/// pages and the `dump` command.
+pub(super) trait TableSpec: Sync + 'static {
+    /// The name in URLs and on the command line.
+    const NAME: &'static str;
+    const TITLE: &'static str;
+    /// The headers of the columns [`TableSpec::cells`] returns.
+    const COLUMNS: &'static [&'static str];
+    /// The roles that may view this table and change its contents.
+    const PERMISSION: Permission;
+    /// The RocksDB column family behind the table, which its entries are
+    /// read from and its size is estimated from.
+    #[cfg(feature = "server")]
+    const COLUMN_FAMILY: Option<&'static str>;
+
+    /// An entry as read from the table.
+    #[cfg(feature = "server")]
+    type Entry: Serialize;
+
+    /// Calls `f` with the key of each entry, if known, and the entry, in
+    /// `direction` from key `from`, or from the first key in that
+    /// direction, until `f` breaks.
+    ///
+    /// Tables in a column family are read through review-database, by
+    /// [`TableSpec::iter`], which reports no key for an entry it fails to
+    /// decode. Without a store, as in a read-only state, they are read from
+    /// the raw key-value pairs by [`TableSpec::decode`] instead.
+    #[cfg(feature = "server")]
+    fn scan(
+        source: &Source,
+        direction: Direction,
+        from: Option<&[u8]>,
+        f: &mut dyn FnMut(Option<Vec<u8>>, anyhow::Result<Self::Entry>) -> ControlFlow<()>,
+    ) -> anyhow::Result<()> {
+        match source.data {
+            Data::Store(store) => Self::iter(store, direction, from, &mut |res| {
+                f(res.as_ref().ok().map(Self::key), res)
+            }),
+            Data::Secondary(db) => {
+                let cf = Self::COLUMN_FAMILY
+                    .ok_or_else(|| anyhow::anyhow!("{} has no column family", Self::NAME))?;
+                db.scan(cf, direction, from, &mut |key, value| {
+                    let entry = Self::decode(&key, &value);
+                    f(Some(key), entry)
+                })
+            }
+        }
+    }
+
+    /// Reads every entry, in key order.
+    #[cfg(feature = "server")]
+    fn read_all(source: &Source) -> anyhow::Result<Entries<Self::Entry>> {
+        use data_encoding::HEXLOWER;
+
+        let mut entries = Entries {
+            rows: Vec::new(),
+            failures: Vec::new(),
+        };
+        let mut position = 0;
+        Self::scan(source, Direction::Forward, None, &mut |key, res| {
+            match res {
+                Ok(entry) => entries.rows.push(entry),
+                Err(e) => entries.failures.push(FailedEntry {
+                    position,
+                    key: key.map(|key| HEXLOWER.encode(&Self::mask_key(&key))),
+                    error: format!("{e:#}"),
+                }),
+            }
+            position += 1;
+            ControlFlow::Continue(())
+        })?;
+        Ok(entries)
+    }
+
+    /// Calls `f` with each entry of the table in `store`, read through
+    /// review-database, the way [`TableSpec::scan`] does.
+    #[cfg(feature = "server")]
+    fn iter(
+        _store: &Store,
+        _direction: Direction,
+        _from: Option<&[u8]>,
+        _f: &mut dyn FnMut(anyhow::Result<Self::Entry>) -> ControlFlow<()>,
+    ) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("{} has no column family", Self::NAME))
+    }
+
+    /// Returns the key review-database stores `entry` under.
+    #[cfg(feature = "server")]
+    fn key(entry: &Self::Entry) -> Vec<u8>;
+
+    /// Decodes an entry of [`TableSpec::COLUMN_FAMILY`] from its raw key and
+    /// value, for the column family browser and for states without a store.
+    ///
+    /// This repeats how review-database encodes the entry, which it does not
+    /// expose; the tests of each table check it against what review-database
+    /// writes.
+    #[cfg(feature = "server")]
+    fn decode(_key: &[u8], _value: &[u8]) -> anyhow::Result<Self::Entry> {
+        Err(anyhow::anyhow!("{} has no column family", Self::NAME))
+    }
+
+    /// Counts the entries of a table without a column family, if that is
+    /// cheaper than reading them all.
+    #[cfg(feature = "server")]
+    fn count(_source: &Source) -> Option<anyhow::Result<u64>> {
+        None
+    }
+
+    /// Renders the entry as a row of the table page.
+    #[cfg(feature = "server")]
+    fn cells(entry: &Self::Entry) -> Vec<String>;
+
+    /// Renders the entry as the `dump` command prints it. Tables override it
+    /// to leave out what their pages hide.
+    #[cfg(feature = "server")]
+    fn dump(entry: &Self::Entry) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(entry)?)
+    }
+
+    /// Decodes a raw value of [`TableSpec::COLUMN_FAMILY`] for the column
+    /// family browser, if it holds more than the key does.
+    #[cfg(feature = "server")]
+    fn decode_value(_value: &[u8]) -> Option<anyhow::Result<String>> {
+        None
+    }
+
+    /// Hides what a raw key of [`TableSpec::COLUMN_FAMILY`] holds that the
+    /// table page masks, wherever raw keys are shown: in the column family
+    /// browser, in page cursors and in the keys of entries that fail to
+    /// decode.
+    #[cfg(feature = "server")]
+    fn mask_key(key: &[u8]) -> Vec<u8> {
+        key.to_vec()
+    }
+
+    /// Finds the raw key [`TableSpec::mask_key`] masked into `masked`, for a
+    /// page cursor. `scan` calls its callback with the raw keys of the column
+    /// family from a key on, until the callback breaks.
+    #[cfg(feature = "server")]
+    fn unmask_key(masked: &[u8], _scan: KeyScan) -> anyhow::Result<Vec<u8>> {
+        Ok(masked.to_vec())
+    }
+
+    /// Renders what the table page shows about the table besides its rows,
+    /// to every role that may read it.
+    fn status(_profile: String) -> Element {
+        rsx! {}
+    }
+
+    /// Renders the forms that change the table, which the table page shows
+    /// to the roles that may write to it. They call `on_change` after each
+    /// change so the page is read again.
+    fn manage(_profile: String, _on_change: EventHandler<()>) -> Element {
+        rsx! {}
+    }
+}
+
+/// The object-safe part of [`TableSpec`], which [`LookUp`] dispatches to.
+trait AnyTable: Sync {
+    fn name(&self) -> &'static str;
+    fn title(&self) -> &'static str;
+    fn columns(&self) -> &'static [&'static str];
+    fn permission(&self) -> Permission;
+    fn status(&self, profile: String) -> Element;
+    fn manage(&self, profile: String, on_change: EventHandler<()>) -> Element;
+    #[cfg(feature = "server")]
+    fn column_family(&self) -> Option<&'static str>;
+    #[cfg(feature = "server")]
+    fn page(
+        &self,
+        source: &Source,
+        cursor: &Cursor,
+        size: usize,
+    ) -> anyhow::Result<Page<Vec<String>>>;
+    #[cfg(feature = "server")]
+    fn entries(&self, source: &Source) -> anyhow::Result<Entries<serde_json::Value>>;
+    #[cfg(feature = "server")]
+    fn count(&self, source: &Source) -> Option<anyhow::Result<u64>>;
+    #[cfg(feature = "server")]
+    fn decode_value(&self, value: &[u8]) -> Option<anyhow::Result<String>>;
+    #[cfg(feature = "server")]
+    fn mask_key(&self, key: &[u8]) -> Vec<u8>;
+    #[cfg(feature = "server")]
+    fn unmask_key(&self, masked: &[u8], scan: KeyScan) -> anyhow::Result<Vec<u8>>;
+}
+
+impl<T: TableSpec> AnyTable for T {
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn title(&self) -> &'static str {
+        T::TITLE
+    }
+
+    fn columns(&self) -> &'static [&'static str] {
+        T::COLUMNS
+    }
+
+    fn permission(&self) -> Permission {
+        T::PERMISSION
+    }
+
+    fn status(&self, profile: String) -> Element {
+        T::status(profile)
+    }
+
+    fn manage(&self, profile: String, on_change: EventHandler<()>) -> Element {
+        T::manage(profile, on_change)
+    }
+
+    #[cfg(feature = "server")]
+    fn column_family(&self) -> Option<&'static str> {
+        T::COLUMN_FAMILY
+    }
+
+    #[cfg(feature = "server")]
+    fn page(
+        &self,
+        source: &Source,
+        cursor: &Cursor,
+        size: usize,
+    ) -> anyhow::Result<Page<Vec<String>>> {
+        let page = read_masked(Some(self), cursor, size, |direction, from, f| {
+            T::scan(source, direction, from, f)
+        })?;
+        Ok(page.map(|entry| T::cells(&entry)))
+    }
+
+    #[cfg(feature = "server")]
+    fn entries(&self, source: &Source) -> anyhow::Result<Entries<serde_json::Value>> {
+        let entries = T::read_all(source)?;
+        Ok(Entries {
+            rows: entries
+                .rows
+                .iter()
+                .map(T::dump)
+                .collect::<anyhow::Result<_>>()?,
+            failures: entries.failures,
+        })
+    }
+
+    #[cfg(feature = "server")]
+    fn count(&self, source: &Source) -> Option<anyhow::Result<u64>> {
+        T::count(source)
+    }
+
+    #[cfg(feature = "server")]
+    fn decode_value(&self, value: &[u8]) -> Option<anyhow::Result<String>> {
+        T::decode_value(value)
+    }
+
+    #[cfg(feature = "server")]
+    fn mask_key(&self, key: &[u8]) -> Vec<u8> {
+        T::mask_key(key)
+    }
+
+    #[cfg(feature = "server")]
+    fn unmask_key(&self, masked: &[u8], scan: KeyScan) -> anyhow::Result<Vec<u8>> {
+        T::unmask_key(masked, scan)
+    }
+}
+
+/// Scans raw keys for [`TableSpec::unmask_key`].
+#[cfg(feature = "server")]
+pub(super) type KeyScan<'a> =
+    &'a mut dyn FnMut(&[u8], &mut dyn FnMut(&[u8]) -> ControlFlow<()>) -> anyhow::Result<()>;
+
+/// Reads a page with `scan` as [`Page::read`] does, with the cursor
+/// unmasked by [`TableSpec::unmask_key`] of `table`, and the cursors and the
+/// keys of failed entries of the page masked by [`TableSpec::mask_key`], so
+/// that what the table page hides never ends up in a URL.
+#[cfg(feature = "server")]
+fn read_masked<T>(
+    table: Option<&dyn AnyTable>,
+    cursor: &Cursor,
+    size: usize,
+    mut scan: impl FnMut(
+        Direction,
+        Option<&[u8]>,
+        &mut dyn FnMut(Option<Vec<u8>>, anyhow::Result<T>) -> ControlFlow<()>,
+    ) -> anyhow::Result<()>,
+) -> anyhow::Result<Page<T>> {
+    let Some(table) = table else {
+        return Page::read(cursor, size, <[u8]>::to_vec, scan);
+    };
+    let cursor = cursor.map_key(|masked| {
+        table.unmask_key(masked, &mut |from, f| {
+            scan(Direction::Forward, Some(from), &mut |key, _| {
+                key.map_or(ControlFlow::Continue(()), |key| f(&key))
+            })
+        })
+    })?;
+    Page::read(&cursor, size, |key| table.mask_key(key), scan)
+}
+
+/// Reads a page of the raw entries of a column family with `scan`, masking
+/// its keys as `table`, the table stored in the column family, if any, masks
+/// them; see [`read_masked`].
+#[cfg(feature = "server")]
+pub(crate) fn read_raw_page<T>(
+    table: Option<LookUp>,
+    cursor: &Cursor,
+    size: usize,
+    scan: impl FnMut(
+        Direction,
+        Option<&[u8]>,
+        &mut dyn FnMut(Option<Vec<u8>>, anyhow::Result<T>) -> ControlFlow<()>,
+    ) -> anyhow::Result<()>,
+) -> anyhow::Result<Page<T>> {
+    read_masked(table.map(|table| table.0), cursor, size, scan)
+}
+
+/// A table in [`TABLES`].
+#[derive(Clone, Copy)]
+pub(crate) struct LookUp(&'static dyn AnyTable);
+
+impl LookUp {
+    pub(crate) fn iter() -> impl Iterator<Item = Self> {
+        TABLES.iter().map(|&table| Self(table))
+    }
+
+    #[cfg(feature = "server")]
+    pub(crate) fn names() -> Vec<String> {
+        Self::iter().map(|t| t.to_string()).collect()
+    }
+
+    /// Returns the table stored in column family `cf`, if any.
+    #[cfg(feature = "server")]
+    pub(crate) fn for_column_family(cf: &str) -> Option<Self> {
+        Self::iter().find(|t| t.0.column_family() == Some(cf))
+    }
+
+    pub(crate) fn title(self) -> &'static str {
+        self.0.title()
+    }
+
+    pub(crate) fn columns(self) -> &'static [&'static str] {
+        self.0.columns()
+    }
+
+    /// The roles that may view this table and change its contents.
+    pub(crate) fn permission(self) -> Permission {
+        self.0.permission()
+    }
+
+    /// Decodes a raw value of the table's column family; see
+    /// [`TableSpec::decode_value`].
+    #[cfg(feature = "server")]
+    pub(crate) fn decode_value(self, value: &[u8]) -> Option<anyhow::Result<String>> {
+        self.0.decode_value(value)
+    }
+
+    /// Masks a raw key of the table's column family; see
+    /// [`TableSpec::mask_key`].
+    #[cfg(feature = "server")]
+    pub(crate) fn mask_key(self, key: &[u8]) -> Vec<u8> {
+        self.0.mask_key(key)
+    }
+}
+
+impl PartialEq for LookUp {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.name() == other.0.name()
+    }
+}
+
+impl fmt::Debug for LookUp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("LookUp").field(&self.0.name()).finish()
+    }
+}
+
+impl fmt::Display for LookUp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0.name())
+    }
+}
+
+impl FromStr for LookUp {
+    type Err = UnknownTable;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|t| t.0.name().eq_ignore_ascii_case(s))
+            .ok_or(UnknownTable)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct UnknownTable;
+
+/// How many samples the overview shows for each table.
+const SAMPLES: usize = 3;
+
+/// The number of entries in a table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Count {
+    Exact(u64),
+    /// RocksDB's estimate, for large tables in a column family.
+    Estimated(u64),
+}
+
+/// What the overview shows about a table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TableDigest {
+    table: String,
+    summary: Result<Summary, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Summary {
+    count: Count,
+    samples: Vec<String>,
+    /// The sampled entries that could not be decoded.
+    failures: usize,
+}
+
+/// What tables are read from: the database, and the backup directory for
+/// what is kept outside the database.
+#[cfg(feature = "server")]
+pub(crate) struct Source<'a> {
+    pub(crate) data: Data<'a>,
+    pub(crate) backup_dir: &'a std::path::Path,
+}
+
+/// The database a [`Source`] reads.
+#[cfg(feature = "server")]
+pub(crate) enum Data<'a> {
+    /// The store of a writable state.
+    Store(&'a Store),
+    /// The secondary instance of the database in the data directory of a
+    /// read-only state, as of its last catch-up.
+    Secondary(&'a rocks::Db),
+}
+
+#[cfg(feature = "server")]
+impl super::State {
+    /// Reads up to `size` rows of `table` at `cursor`.
+    pub(crate) async fn table_page(
+        &self,
+        table: LookUp,
+        cursor: &Cursor,
+        size: usize,
+    ) -> anyhow::Result<Page<Vec<String>>> {
+        let cursor = cursor.clone();
+        let page = self
+            .with_source(move |source| table.0.page(source, &cursor, size))
+            .await?;
+        self.mark_read();
+        Ok(page)
+    }
+
+    /// Reads every entry of `table`.
+    pub(crate) async fn entries(
+        &self,
+        table: LookUp,
+    ) -> anyhow::Result<Entries<serde_json::Value>> {
+        let entries = self
+            .with_source(move |source| table.0.entries(source))
+            .await?;
+        self.mark_read();
+        Ok(entries)
+    }
+
+    /// Estimates the rows of `table` without reading them: from `stats` for a
+    /// table in a column family, or else by [`TableSpec::count`]. Returns
+    /// `None` if the table cannot be counted that way.
+    pub(crate) async fn estimated_rows(
+        &self,
+        table: LookUp,
+        stats: &[super::rocks::ColumnFamilyStats],
+    ) -> anyhow::Result<Option<u64>> {
+        if let Some(cf) = table.0.column_family() {
+            return Ok(stats
+                .iter()
+                .find(|s| s.name == cf)
+                .map(|s| s.estimated_keys));
+        }
+        self.with_source(move |source| table.0.count(source).transpose())
+            .await
+    }
+
+    /// Summarizes `table` from its first `samples` entries. `stats` are the
+    /// column family statistics the counts of large tables are estimated
+    /// from; tables without a column family are counted by
+    /// [`TableSpec::count`], or else in full.
+    async fn summary(
+        &self,
+        table: LookUp,
+        samples: usize,
+        stats: &[super::rocks::ColumnFamilyStats],
+    ) -> anyhow::Result<Summary> {
+        let page = self.table_page(table, &Cursor::First, samples).await?;
+        let sampled = (page.rows.len() + page.failures.len()) as u64;
+        let count = match (&page.next, table.0.column_family()) {
+            (None, _) => Count::Exact(sampled),
+            (Some(_), Some(cf)) => {
+                let estimate = stats
+                    .iter()
+                    .find(|s| s.name == cf)
+                    .map_or(0, |s| s.estimated_keys);
+                Count::Estimated(estimate.max(sampled))
+            }
+            (Some(_), None) => {
+                let counted = self
+                    .with_source(move |source| table.0.count(source).transpose())
+                    .await?;
+                match counted {
+                    Some(count) => Count::Exact(count),
+                    None => {
+                        let entries = self.entries(table).await?;
+                        Count::Exact((entries.rows.len() + entries.failures.len()) as u64)
+                    }
+                }
+            }
+        };
+        Ok(Summary {
+            count,
+            samples: page.rows.iter().map(|cells| cells.join(", ")).collect(),
+            failures: page.failures.len(),
+        })
+    }
+}
+
+/// Summarizes every table the signed-in user may read.
+#[server]
+async fn digests(profile: String) -> Result<Vec<TableDigest>, ServerFnError> {
+    let user = super::auth::current_user().await?;
+    let review = super::state::review(&profile).await?;
+
+    let stats = review.column_family_stats().await.unwrap_or_else(|e| {
+        dioxus_logger::tracing::warn!("cannot estimate table sizes: {e:#}");
+        Vec::new()
+    });
+    let mut digests = Vec::new();
+    for table in LookUp::iter().filter(|t| t.permission().allows(user.role, Access::Read)) {
+        let summary = review
+            .summary(table, SAMPLES, &stats)
+            .await
+            .map_err(|e| e.to_string());
+        digests.push(TableDigest {
+            table: table.to_string(),
+            summary,
+        });
+    }
+    Ok(digests)
+}
 
 #[component]
-pub fn Digest() -> Element {
+pub fn Digest(profile: String) -> Element {
+    let digests = use_server_future(use_reactive((&profile,), |(profile,)| digests(profile)))?;
     rsx! {
         table { style: "table-layout: fixed;
                 max-width: 100%; max-height: 600px;
@@ -36,36 +603,682 @@ pub fn Digest() -> Element {
                 }
             }
             tbody { style: "vertical-align: top;",
-                access_token::Digest {}
-                account::Digest {}
-                super::BackupDigest {}
+                match digests() {
+                    None => rsx! { tr { td { colspan: 3, "Loading..." } } },
+                    Some(Err(e)) => rsx! { tr { td { colspan: 3, "{e}" } } },
+                    Some(Ok(digests)) => rsx! {
+                        for digest in digests {
+                            DigestRow { profile: profile.clone(), digest }
+                        }
+                    },
+                }
             }
             tfoot { style: "font-color: rgba(0, 0, 0, 0.5); border-top: 1px solid rgba(0, 0, 0, 0.5)",
                 tr {
-                    th { colspan: 3, super::Info {} }
+                    th { colspan: 3, super::Info { profile: profile.clone() } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DigestRow(profile: String, digest: TableDigest) -> Element {
+    let title = LookUp::from_str(&digest.table).map_or("", LookUp::title);
+    rsx! {
+        tr {
+            th { style: "width: 200px; text-align: right;", scope: "row",
+                Link {
+                    class: "hover:bg-gray-100",
+                    to: crate::Route::Table {
+                        profile,
+                        name: digest.table.clone(),
+                        after: String::new(),
+                        before: String::new(),
+                    },
+                    {title}
+                }
+            }
+            match digest.summary {
+                Err(e) => rsx! { td { colspan: 2, "{e}" } },
+                Ok(summary) => rsx! {
+                    td { style: "width: 100px; text-align: center;",
+                        match summary.count {
+                            Count::Exact(n) => rsx! { "{n}" },
+                            Count::Estimated(n) => rsx! {
+                                span { title: "estimated by RocksDB", "≈{n}" }
+                            },
+                        }
+                        FailureCount { failures: summary.failures }
+                    }
+                    td {
+                        ol {
+                            for sample in summary.samples {
+                                li {
+                                    p { style: "word-break: break-word;", "{sample}" }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Formats a time the way table pages show it.
+#[cfg(feature = "server")]
+pub(crate) fn format_time(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// The entries of a table, apart from those that could not be decoded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Entries<T> {
+    pub(crate) rows: Vec<T>,
+    pub(crate) failures: Vec<FailedEntry>,
+}
+
+/// An entry that could not be decoded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FailedEntry {
+    /// The position of the entry in key order, on its page or in the whole
+    /// table.
+    pub(crate) position: usize,
+    /// The raw key in hex, masked as the table page masks it, if it is
+    /// known.
+    pub(crate) key: Option<String>,
+    pub(crate) error: String,
+}
+
+/// How many rows a table page shows.
+const PAGE_SIZE: usize = 50;
+
+/// Where a page of a table starts, given as the hex-encoded key of a row
+/// next to it, masked by [`TableSpec::mask_key`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Cursor {
+    First,
+    /// The rows after this key.
+    After(String),
+    /// The rows before this key.
+    Before(String),
+}
+
+impl Cursor {
+    /// Reads the cursor from the `after` and `before` query parameters of
+    /// [`crate::Route::Table`], where an empty string means unset.
+    pub(crate) fn new(after: &str, before: &str) -> Self {
+        if !after.is_empty() {
+            Self::After(after.to_string())
+        } else if !before.is_empty() {
+            Self::Before(before.to_string())
+        } else {
+            Self::First
+        }
+    }
+
+    /// Replaces the raw key of the cursor with what `f` returns for it.
+    #[cfg(feature = "server")]
+    fn map_key(&self, f: impl FnOnce(&[u8]) -> anyhow::Result<Vec<u8>>) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        use data_encoding::HEXLOWER;
+
+        let map = |key: &str| -> anyhow::Result<String> {
+            let key = HEXLOWER.decode(key.as_bytes()).context("invalid cursor")?;
+            Ok(HEXLOWER.encode(&f(&key)?))
+        };
+        Ok(match self {
+            Self::First => Self::First,
+            Self::After(key) => Self::After(map(key)?),
+            Self::Before(key) => Self::Before(map(key)?),
+        })
+    }
+
+    /// Returns the `after` and `before` query parameters for the cursor.
+    pub(crate) fn to_query(&self) -> (String, String) {
+        match self {
+            Self::First => (String::new(), String::new()),
+            Self::After(key) => (key.clone(), String::new()),
+            Self::Before(key) => (String::new(), key.clone()),
+        }
+    }
+}
+
+/// A page of a table, in key order, with the cursors of its neighbors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Page<T> {
+    pub(crate) rows: Vec<T>,
+    pub(crate) failures: Vec<FailedEntry>,
+    pub(crate) prev: Option<Cursor>,
+    pub(crate) next: Option<Cursor>,
+}
+
+#[cfg(feature = "server")]
+impl<T> Page<T> {
+    pub(crate) fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            rows: self.rows.into_iter().map(f).collect(),
+            failures: self.failures,
+            prev: self.prev,
+            next: self.next,
+        }
+    }
+
+    /// Reads up to `size` rows at `cursor` with `scan`, which calls its
+    /// callback with the key of each entry, if known, and the entry, in a
+    /// direction from a key, the way [`TableSpec::scan`] does.
+    ///
+    /// Entries that fail to decode count towards `size`, and their positions
+    /// are counted from the start of the page. Only known keys become
+    /// cursors, masked by `mask` as the keys of failed entries are.
+    pub(crate) fn read(
+        cursor: &Cursor,
+        size: usize,
+        mask: impl Fn(&[u8]) -> Vec<u8>,
+        scan: impl FnOnce(
+            Direction,
+            Option<&[u8]>,
+            &mut dyn FnMut(Option<Vec<u8>>, anyhow::Result<T>) -> ControlFlow<()>,
+        ) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        use data_encoding::HEXLOWER;
+
+        let decode = |key: &str| HEXLOWER.decode(key.as_bytes()).context("invalid cursor");
+        let encode = |key: &[u8]| HEXLOWER.encode(&mask(key));
+        let (direction, from) = match cursor {
+            Cursor::First => (Direction::Forward, None),
+            Cursor::After(key) => (Direction::Forward, Some(decode(key)?)),
+            Cursor::Before(key) => (Direction::Reverse, Some(decode(key)?)),
+        };
+        let mut rows = Vec::new();
+        let mut failures = Vec::new();
+        // The known keys of the entries on the page, in the order they were
+        // read.
+        let mut keys: Vec<Vec<u8>> = Vec::new();
+        let mut taken = 0;
+        let mut more = false;
+        scan(direction, from.as_deref(), &mut |key, res| {
+            // The entry at the cursor belongs to the neighboring page.
+            if key.is_some() && key.as_deref() == from.as_deref() {
+                return ControlFlow::Continue(());
+            }
+            if taken == size {
+                more = true;
+                return ControlFlow::Break(());
+            }
+            match res {
+                Ok(row) => rows.push(row),
+                Err(e) => failures.push(FailedEntry {
+                    position: taken,
+                    key: key.as_deref().map(encode),
+                    error: format!("{e:#}"),
+                }),
+            }
+            keys.extend(key);
+            taken += 1;
+            ControlFlow::Continue(())
+        })?;
+        if matches!(cursor, Cursor::Before(_)) {
+            rows.reverse();
+            keys.reverse();
+            failures.reverse();
+            for failure in &mut failures {
+                failure.position = taken - 1 - failure.position;
+            }
+        }
+
+        let cursor_key = || from.as_deref().map(encode);
+        let first = keys.first().map(|key| encode(key)).or_else(cursor_key);
+        let last = keys.last().map(|key| encode(key)).or_else(cursor_key);
+        let (prev, next) = match cursor {
+            Cursor::First => (None, last.filter(|_| more).map(Cursor::After)),
+            Cursor::After(_) => (
+                first.map(Cursor::Before),
+                last.filter(|_| more).map(Cursor::After),
+            ),
+            Cursor::Before(_) => (
+                first.filter(|_| more).map(Cursor::Before),
+                last.map(Cursor::After),
+            ),
+        };
+        Ok(Self {
+            rows,
+            failures,
+            prev,
+            next,
+        })
+    }
+}
+
+/// Links to the neighbors of a page.
+#[component]
+pub(crate) fn Pager(prev: Option<crate::Route>, next: Option<crate::Route>) -> Element {
+    rsx! {
+        nav { style: "display: flex; flex-direction: row; gap: 16px; margin: 8px 0;",
+            if let Some(prev) = prev {
+                Link { class: "hover:bg-gray-100", to: prev, "← Previous" }
+            } else {
+                span { style: "color: gray;", "← Previous" }
+            }
+            if let Some(next) = next {
+                Link { class: "hover:bg-gray-100", to: next, "Next →" }
+            } else {
+                span { style: "color: gray;", "Next →" }
+            }
+        }
+    }
+}
+
+/// What the forms that change a table report after an action.
+pub(crate) type Status = Option<Result<String, String>>;
+
+/// Shows the result of the last action of a form.
+#[component]
+pub(crate) fn StatusLine(status: Status) -> Element {
+    match status {
+        None => rsx! {},
+        Some(Ok(message)) => rsx! { p { color: "green", "{message}" } },
+        Some(Err(e)) => rsx! { p { color: "red", "{e}" } },
+    }
+}
+
+/// Asks whether to go ahead with an action that cannot be undone, which
+/// `children` describes.
+#[component]
+pub(crate) fn Confirm(
+    on_confirm: EventHandler<()>,
+    on_cancel: EventHandler<()>,
+    children: Element,
+) -> Element {
+    rsx! {
+        div { style: "padding: 8px; border: 2px solid orange; background-color: rgb(254, 243, 199);",
+            {children}
+            button { class: "hover:bg-gray-100", style: "margin-left: 8px;", onclick: move |_| on_confirm.call(()), "Confirm" }
+            button { class: "hover:bg-gray-100", style: "margin-left: 8px;", onclick: move |_| on_cancel.call(()), "Cancel" }
+        }
+    }
+}
+
+/// Lists the entries of a table that could not be decoded.
+#[component]
+pub(crate) fn Failures(failures: Vec<FailedEntry>) -> Element {
+    if failures.is_empty() {
+        return rsx! {};
+    }
+    rsx! {
+        div {
+            style: "margin: 8px 0; padding: 8px; border: 2px solid orange; background-color: rgb(254, 243, 199);",
+            h2 { style: "font: small-caps bold 18px sans-serif;",
+                "{failures.len()} entries could not be decoded"
+            }
+            table {
+                thead {
+                    tr {
+                        th { scope: "col", "Position" }
+                        th { scope: "col", "Key" }
+                        th { scope: "col", "Error" }
+                    }
+                }
+                tbody {
+                    for failure in failures {
+                        tr {
+                            td { "{failure.position}" }
+                            td { style: "font-family: monospace; word-break: break-all;",
+                                match failure.key {
+                                    Some(key) => rsx! { "{key}" },
+                                    None => rsx! { span { style: "color: gray;", "unknown" } },
+                                }
+                            }
+                            td { "{failure.error}" }
+                        }
+                    }
                 }
             }
         }
     }
 }
 
-#[derive(Debug, PartialEq, EnumString, strum_macros::Display)]
-#[strum(ascii_case_insensitive, serialize_all = "snake_case")]
-pub(crate) enum LookUp {
-    AccessToken,
-    Account,
-    Backup,
+/// Flags a count in the overview that includes entries that could not be
+/// decoded.
+#[component]
+fn FailureCount(failures: usize) -> Element {
+    if failures == 0 {
+        return rsx! {};
+    }
+    rsx! {
+        span { style: "color: darkorange;", title: "entries that could not be decoded",
+            " (+{failures} unreadable)"
+        }
+    }
+}
+
+#[server]
+async fn table_page(
+    profile: String,
+    name: String,
+    cursor: Cursor,
+    size: usize,
+) -> Result<Page<Vec<String>>, ServerFnError> {
+    let table = LookUp::from_str(&name).map_err(|_| ServerFnError::new("unknown table"))?;
+    super::auth::authorize(table.permission(), Access::Read).await?;
+    let review = super::state::review(&profile).await?;
+
+    review
+        .table_page(table, &cursor, size.min(PAGE_SIZE))
+        .await
+        .map_err(ServerFnError::new)
+}
+
+/// Sorts rows by the cells in column `sort.0`, in descending order if
+/// `sort.1` is set. Numbers come first, in numeric order, then the other
+/// cells in text order.
+fn sorted(mut rows: Vec<Vec<String>>, sort: Option<(usize, bool)>) -> Vec<Vec<String>> {
+    use std::cmp::Ordering;
+
+    let Some((column, descending)) = sort else {
+        return rows;
+    };
+    let number = |cell: Option<&String>| cell.and_then(|cell| cell.parse::<f64>().ok());
+    rows.sort_by(|a, b| {
+        let (a, b) = (a.get(column), b.get(column));
+        match (number(a), number(b)) {
+            (Some(x), Some(y)) => x.total_cmp(&y).then_with(|| a.cmp(&b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.cmp(&b),
+        }
+    });
+    if descending {
+        rows.reverse();
+    }
+    rows
 }
 
+/// Shows a page of a table. Sorting a column orders the rows of the page,
+/// not the whole table, which is always paged in key order.
+///
+/// `revision` changes whenever the table is changed, to read the page again.
 #[component]
-pub fn Table(name: String) -> Element {
-    match LookUp::from_str(&name) {
-        #[allow(clippy::match_single_binding)]
-        Ok(l) => match l {
-            LookUp::AccessToken => access_token::Full(),
-            LookUp::Account => account::Full(),
-            LookUp::Backup => super::state::Full(),
+fn Full(profile: String, table: LookUp, cursor: Cursor, revision: u32) -> Element {
+    let page = use_server_future(use_reactive(
+        (&profile, &table, &cursor, &revision),
+        |(profile, table, cursor, _)| table_page(profile, table.to_string(), cursor, PAGE_SIZE),
+    ))?;
+    let columns = table.columns();
+    // The column the page is sorted by, and whether in descending order.
+    let mut sort = use_signal(|| None::<(usize, bool)>);
+    let arrow = move |column: usize| match sort() {
+        Some((c, false)) if c == column => " ▲",
+        Some((c, true)) if c == column => " ▼",
+        _ => "",
+    };
+    let link = |cursor: Cursor| {
+        let (after, before) = cursor.to_query();
+        crate::Route::Table {
+            profile: profile.clone(),
+            name: table.to_string(),
+            after,
+            before,
+        }
+    };
+    rsx! {
+        if let Some(Ok(page)) = page() {
+            Failures { failures: page.failures }
+        }
+        p { style: "color: gray; margin: 8px 0;",
+            match sort() {
+                Some((column, _)) => {
+                    let column = columns[column];
+                    rsx! {
+                        "Sorted by {column} on this page only; other pages are not included, and pages follow key order. "
+                        button {
+                            class: "hover:bg-gray-100",
+                            style: "border: 1px solid rgba(0, 0, 0, 0.5); padding: 0 4px;",
+                            onclick: move |_| sort.set(None),
+                            "Key order"
+                        }
+                    }
+                }
+                None => rsx! {
+                    "Click a column header to sort the rows on this page; pages follow key order."
+                },
+            }
+        }
+        table { style: "table-layout: fixed;
+                max-width: 1200px; max-height: 1200px;
+                overflow: auto; display: block;
+                border-spacing: 0;",
+            caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
+                {table.title()}
+            }
+            thead {
+                tr { style: "position: sticky; top: 0; background: rgba(0, 0, 0, 0.1);",
+                    for (i, column) in columns.iter().enumerate() {
+                        th {
+                            scope: "col",
+                            style: "cursor: pointer;",
+                            title: "sort this page by {column}",
+                            onclick: move |_| {
+                                // Ascending, then descending, then key order.
+                                sort.set(match sort() {
+                                    Some((c, false)) if c == i => Some((i, true)),
+                                    Some((c, true)) if c == i => None,
+                                    _ => Some((i, false)),
+                                });
+                            },
+                            "{column}{arrow(i)}"
+                        }
+                    }
+                }
+            }
+            tbody {
+                match page() {
+                    None => rsx! { tr { td { colspan: "{columns.len()}", "Loading..." } } },
+                    Some(Err(e)) => rsx! { tr { td { colspan: "{columns.len()}", "{e}" } } },
+                    Some(Ok(page)) => rsx! {
+                        for cells in sorted(page.rows, sort()) {
+                            tr { class: "odd:bg-white even:bg-gray-100",
+                                for cell in cells {
+                                    td { style: "word-break: break-word;", "{cell}" }
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+        if let Some(Ok(page)) = page() {
+            Pager { prev: page.prev.map(&link), next: page.next.map(&link) }
+        }
+    }
+}
+
+#[component]
+pub fn Table(profile: String, name: String, after: String, before: String) -> Element {
+    let mut revision = use_signal(|| 0_u32);
+    let me = use_server_future(super::whoami)?;
+    let Ok(table) = LookUp::from_str(&name) else {
+        return crate::components::Coming();
+    };
+    match me() {
+        None => rsx! { p { "Loading..." } },
+        Some(Err(e)) => rsx! { p { "{e}" } },
+        Some(Ok(me)) if !table.permission().allows(me.role, Access::Read) => rsx! {
+            crate::components::Forbidden { role: me.role.to_string() }
+        },
+        Some(Ok(me)) => rsx! {
+            Full {
+                profile: profile.clone(),
+                table,
+                cursor: Cursor::new(&after, &before),
+                revision: revision(),
+            }
+            {table.0.status(profile.clone())}
+            if table.permission().allows(me.role, Access::Write) {
+                {table.0.manage(profile, EventHandler::new(move |()| *revision.write() += 1))}
+            }
         },
-        Err(_) => crate::components::Coming(),
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use review_database::Direction;
+
+    use super::{sorted, Cursor, Page};
+
+    #[test]
+    fn cursor_from_query() {
+        assert_eq!(Cursor::new("", ""), Cursor::First);
+        assert_eq!(Cursor::new("0a", ""), Cursor::After("0a".to_string()));
+        assert_eq!(Cursor::new("", "0b"), Cursor::Before("0b".to_string()));
+        assert_eq!(Cursor::new("0a", "0b"), Cursor::After("0a".to_string()));
+    }
+
+    #[test]
+    fn cursor_to_query() {
+        for cursor in [
+            Cursor::First,
+            Cursor::After("0a".to_string()),
+            Cursor::Before("0b".to_string()),
+        ] {
+            let (after, before) = cursor.to_query();
+            assert_eq!(Cursor::new(&after, &before), cursor);
+        }
+    }
+
+    /// Reads a page of entries keyed 1 to 9, of which those in `broken`
+    /// fail to decode.
+    fn read(cursor: &Cursor, size: usize, broken: &[u8]) -> Page<u8> {
+        let keys: Vec<u8> = (1..=9).collect();
+        Page::read(cursor, size, <[u8]>::to_vec, |direction, from, f| {
+            let mut keys = keys.clone();
+            if matches!(direction, Direction::Reverse) {
+                keys.reverse();
+            }
+            for key in keys {
+                let started = match (from, direction) {
+                    (None, _) => true,
+                    (Some(from), Direction::Forward) => [key].as_slice() >= from,
+                    (Some(from), Direction::Reverse) => [key].as_slice() <= from,
+                };
+                if !started {
+                    continue;
+                }
+                let entry = if broken.contains(&key) {
+                    Err(anyhow::anyhow!("broken"))
+                } else {
+                    Ok(key)
+                };
+                if f(Some(vec![key]), entry).is_break() {
+                    break;
+                }
+            }
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn first_page() {
+        let page = read(&Cursor::First, 3, &[]);
+        assert_eq!(page.rows, [1, 2, 3]);
+        assert_eq!(page.prev, None);
+        assert_eq!(page.next, Some(Cursor::After("03".to_string())));
+    }
+
+    #[test]
+    fn skips_the_row_at_the_cursor() {
+        let page = read(&Cursor::After("03".to_string()), 3, &[]);
+        assert_eq!(page.rows, [4, 5, 6]);
+        assert_eq!(page.prev, Some(Cursor::Before("04".to_string())));
+        assert_eq!(page.next, Some(Cursor::After("06".to_string())));
+
+        let page = read(&Cursor::Before("04".to_string()), 3, &[]);
+        assert_eq!(page.rows, [1, 2, 3]);
+        assert_eq!(page.prev, None);
+        assert_eq!(page.next, Some(Cursor::After("03".to_string())));
+    }
+
+    #[test]
+    fn last_page() {
+        let page = read(&Cursor::After("06".to_string()), 3, &[]);
+        assert_eq!(page.rows, [7, 8, 9]);
+        assert_eq!(page.next, None);
+
+        let page = read(&Cursor::After("09".to_string()), 3, &[]);
+        assert!(page.rows.is_empty());
+        assert_eq!(page.prev, Some(Cursor::Before("09".to_string())));
+        assert_eq!(page.next, None);
+    }
+
+    #[test]
+    fn failures_count_towards_size() {
+        let page = read(&Cursor::First, 3, &[2]);
+        assert_eq!(page.rows, [1, 3]);
+        assert_eq!(page.failures.len(), 1);
+        assert_eq!(page.failures[0].key.as_deref(), Some("02"));
+        assert_eq!(page.failures[0].position, 1);
+        assert_eq!(page.next, Some(Cursor::After("03".to_string())));
+
+        // A page ending in a failure continues after its key.
+        let page = read(&Cursor::First, 3, &[3]);
+        assert_eq!(page.rows, [1, 2]);
+        assert_eq!(page.next, Some(Cursor::After("03".to_string())));
+    }
+
+    #[test]
+    fn failures_on_a_backward_page() {
+        let page = read(&Cursor::Before("07".to_string()), 3, &[4, 6]);
+        assert_eq!(page.rows, [5]);
+        let failures: Vec<_> = page
+            .failures
+            .iter()
+            .map(|failure| (failure.position, failure.key.as_deref()))
+            .collect();
+        assert_eq!(failures, [(0, Some("04")), (2, Some("06"))]);
+        assert_eq!(page.prev, Some(Cursor::Before("04".to_string())));
+        assert_eq!(page.next, Some(Cursor::After("06".to_string())));
+    }
+
+    #[test]
+    fn unknown_keys_are_not_cursors() {
+        // Entry 3 fails to decode where review-database reports no key.
+        let page = Page::read(&Cursor::First, 3, <[u8]>::to_vec, |_, _, f| {
+            for key in 1..=4_u8 {
+                let (key, entry) = if key == 3 {
+                    (None, Err(anyhow::anyhow!("broken")))
+                } else {
+                    (Some(vec![key]), Ok(key))
+                };
+                if f(key, entry).is_break() {
+                    break;
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(page.rows, [1, 2]);
+        assert_eq!(page.failures[0].key, None);
+        assert_eq!(page.failures[0].position, 2);
+        assert_eq!(page.next, Some(Cursor::After("02".to_string())));
+    }
+
+    #[test]
+    fn sorts_numbers_before_text() {
+        let rows = ["10", "b", "9", "a", "1.5"]
+            .map(|cell| vec![cell.to_string()])
+            .to_vec();
+        let cells = |rows: Vec<Vec<String>>| rows.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(
+            cells(sorted(rows.clone(), Some((0, false)))),
+            ["1.5", "9", "10", "a", "b"]
+        );
+        assert_eq!(
+            cells(sorted(rows, Some((0, true)))),
+            ["b", "a", "10", "9", "1.5"]
+        );
     }
 }
@@ -1,65 +1,160 @@
+mod sessions;
 #[cfg(feature = "server")]
-use anyhow::Result;
-use dioxus::prelude::*;
-use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
 
 #[cfg(feature = "server")]
-use crate::server::{state::review, State};
+use anyhow::{Context, Result};
+#[cfg(feature = "server")]
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use review_database::{Direction, Store};
+#[cfg(feature = "server")]
+use serde::Serialize;
 
+use super::TableSpec;
 #[cfg(feature = "server")]
-impl State {
-    pub async fn access_tokens(&self) -> Result<Vec<ATokenProps>> {
-        use review_database::{Direction, Iterable};
+use super::{format_time, KeyScan};
+use crate::server::permission::{Permission, ADMINISTRATORS};
+
+pub(super) struct AccessTokens;
+
+impl TableSpec for AccessTokens {
+    const NAME: &'static str = "access_token";
+    const TITLE: &'static str = "Access Tokens";
+    const COLUMNS: &'static [&'static str] = &["User Name", "Token", "Expires"];
+    const PERMISSION: Permission = ADMINISTRATORS;
+    #[cfg(feature = "server")]
+    const COLUMN_FAMILY: Option<&'static str> = Some("access_tokens");
+
+    #[cfg(feature = "server")]
+    type Entry = ATokenProps;
+
+    #[cfg(feature = "server")]
+    fn iter(
+        store: &Store,
+        direction: Direction,
+        from: Option<&[u8]>,
+        f: &mut dyn FnMut(Result<ATokenProps>) -> ControlFlow<()>,
+    ) -> Result<()> {
+        use review_database::Iterable;
 
-        let store = self.store.read().await;
         let table = store.access_token_map();
-        Ok(table
-            .iter(Direction::Forward, None)
-            .filter_map(|res| match res {
-                Ok(item) => Some(ATokenProps::from(item)),
-                Err(e) => {
-                    dioxus_logger::tracing::error!("Error retrieving access token: {:?}", e);
-                    None
-                }
-            })
-            .collect())
+        for res in table.iter(direction, from) {
+            if f(res.map(ATokenProps::from)).is_break() {
+                break;
+            }
+        }
+        Ok(())
     }
-}
 
-#[server]
-async fn access_token_entries() -> Result<Vec<ATokenProps>, ServerFnError> {
-    let review = review().await?;
+    #[cfg(feature = "server")]
+    fn key(entry: &ATokenProps) -> Vec<u8> {
+        let mut key = entry.username.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(entry.token.as_bytes());
+        key
+    }
 
-    review.access_tokens().await.map_err(ServerFnError::new)
-}
+    /// Review-database stores a token under its username and the token,
+    /// separated by a NUL byte, with nothing in the value.
+    #[cfg(feature = "server")]
+    fn decode(key: &[u8], _value: &[u8]) -> Result<ATokenProps> {
+        let at = key
+            .iter()
+            .position(|&b| b == 0)
+            .context("no separator between the username and the token")?;
+        let username = std::str::from_utf8(&key[..at]).context("invalid username")?;
+        let token = std::str::from_utf8(&key[at + 1..]).context("invalid token")?;
+        Ok(ATokenProps {
+            username: username.to_string(),
+            token: token.to_string(),
+        })
+    }
 
-#[derive(PartialEq, Props, Clone, Serialize, Deserialize)]
-pub struct ATokenProps {
-    username: String,
-    token: String,
-}
+    /// Masks the token; the session view reveals it on request.
+    #[cfg(feature = "server")]
+    fn cells(entry: &ATokenProps) -> Vec<String> {
+        vec![
+            entry.username.clone(),
+            mask(&entry.token),
+            expiry(&entry.token).map(format_time).unwrap_or_default(),
+        ]
+    }
+
+    /// Masks the token as the table page does.
+    #[cfg(feature = "server")]
+    fn dump(entry: &ATokenProps) -> Result<serde_json::Value> {
+        Ok(serde_json::json!({
+            "username": entry.username,
+            "token": mask(&entry.token),
+            "expires": expiry(&entry.token).map(format_time),
+        }))
+    }
 
-#[component]
-fn Entry(entry: ATokenProps) -> Element {
-    rsx! {
-        h1 { style: "word-break: break-word;",
-            "{entry.username}: "
-            {entry.token}
+    /// Masks the token after the username.
+    #[cfg(feature = "server")]
+    fn mask_key(key: &[u8]) -> Vec<u8> {
+        let Some(at) = key.iter().position(|&b| b == 0) else {
+            return mask("").into_bytes();
+        };
+        let token = std::str::from_utf8(&key[at + 1..]).unwrap_or_default();
+        let mut masked = key[..=at].to_vec();
+        masked.extend_from_slice(mask(token).as_bytes());
+        masked
+    }
+
+    /// Looks for the token among those of the username in `masked`, or
+    /// starts at the first of them if it is gone.
+    #[cfg(feature = "server")]
+    fn unmask_key(masked: &[u8], scan: KeyScan) -> Result<Vec<u8>> {
+        let Some(at) = masked.iter().position(|&b| b == 0) else {
+            return Ok(masked.to_vec());
+        };
+        let prefix = &masked[..=at];
+        let mut found = None;
+        scan(prefix, &mut |key| {
+            if !key.starts_with(prefix) {
+                return ControlFlow::Break(());
+            }
+            if Self::mask_key(key) == masked {
+                found = Some(key.to_vec());
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        })?;
+        Ok(found.unwrap_or_else(|| prefix.to_vec()))
+    }
+
+    fn manage(profile: String, on_change: EventHandler<()>) -> Element {
+        rsx! {
+            sessions::Sessions { profile, on_change }
         }
     }
 }
 
-#[component]
-fn Row(entry: ATokenProps) -> Element {
-    rsx! {
-        tr {
-            class: "odd:bg-white even:bg-gray-100",
-            th { "{entry.username}" }
-            td { "{entry.token}" }
-        }
+/// Shows only the ends of `token`, enough to tell tokens apart.
+#[cfg(feature = "server")]
+fn mask(token: &str) -> String {
+    match (token.get(..6), token.get(token.len().saturating_sub(4)..)) {
+        (Some(head), Some(tail)) if token.len() > 16 => format!("{head}…{tail}"),
+        _ => "••••".to_string(),
     }
 }
 
+/// Returns when `token` expires, according to its unverified `exp` claim.
+#[cfg(feature = "server")]
+fn expiry(token: &str) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(crate::server::auth::jwt_expiry(token)?, 0)
+}
+
+#[cfg(feature = "server")]
+#[derive(Serialize)]
+pub(super) struct ATokenProps {
+    username: String,
+    token: String,
+}
+
 #[cfg(feature = "server")]
 impl From<review_database::AccessToken> for ATokenProps {
     fn from(input: review_database::AccessToken) -> Self {
@@ -70,70 +165,113 @@ impl From<review_database::AccessToken> for ATokenProps {
     }
 }
 
-#[component]
-pub(crate) fn Digest() -> Element {
-    let entries = use_server_future(access_token_entries)?;
-    rsx! {
-        tr {
-            th { style: "width: 200px; text-align: right;", scope: "row",
-                Link {
-                    class: "hover:bg-gray-100",
-                    to: crate::Route::Table {
-                        name: super::LookUp::AccessToken.to_string(),
-                    },
-                    "Access Tokens"
-                }
-            }
-            match entries() {
-                None => rsx!{td {colspan: 2, "Loading..."}},
-                Some(Err(e)) => rsx!{td {colspan: 2, "{e}"}},
-                Some(Ok(entries)) => rsx!{
-                    td { style: "width: 100px; text-align: center;", "{entries.len()}" }
-                    td {
-                        ol {
-                            for entry in entries.into_iter().take(3) {
-                                li {
-                                    Entry { entry: entry.clone() }
-                                }
-                            }
-                        }
+#[cfg(feature = "server")]
+impl crate::server::State {
+    /// Checks whether `token` is one of the access tokens of account
+    /// `username`. Only the tokens of the account are read, starting at the
+    /// `username\0token` key they are stored under, and each is compared in
+    /// constant time.
+    pub(crate) async fn has_token(&self, username: &str, token: &str) -> Result<bool> {
+        use crate::server::auth::constant_time_eq;
+
+        let (username, token) = (username.to_string(), token.to_string());
+        self.with_source(move |source| {
+            let mut from = username.as_bytes().to_vec();
+            from.push(0);
+            let mut found = false;
+            AccessTokens::scan(
+                source,
+                Direction::Forward,
+                Some(from.as_slice()),
+                &mut |_, res| {
+                    let Ok(entry) = res else {
+                        return ControlFlow::Continue(());
+                    };
+                    if entry.username != username {
+                        return ControlFlow::Break(());
                     }
-                }
-            }
-        }
+                    found = constant_time_eq(entry.token.as_bytes(), token.as_bytes());
+                    if found {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                },
+            )?;
+            Ok(found)
+        })
+        .await
     }
 }
 
-#[component]
-pub(crate) fn Full() -> Element {
-    let entries = use_server_future(access_token_entries)?;
-    rsx! {
-        table { style: "table-layout: fixed;
-                max-width: 1200px; max-height: 1200px;
-                overflow: auto; display: block;
-                border-spacing: 0;",
-            caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
-                "Access Tokens"
-            }
-            thead {
-                tr { style: "position: sticky; top: 0; background: rgba(0, 0, 0, 0.1);",
-                    th {
-                        style: "width: 200px; text-align: right;",
-                        scope: "col",
-                        "User Name"
-                    }
-                    th { scope: "col", "Token" }
-                }
-                match entries() {
-                    None => rsx!{td {colspan: 2, "Loading..."}},
-                    Some(Err(e)) => rsx!{td {colspan: 2, "{e}"}},
-                    Some(Ok(entries)) => rsx!{
-                        for entry in entries.into_iter() {
-                            Row { entry }
-                        }
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use review_database::Store;
+
+    use super::{ATokenProps, AccessTokens};
+    use crate::server::{rocks, tables::TableSpec};
+
+    #[test]
+    fn cursors_hide_tokens() {
+        let token = |n| format!("eyJhbGciOiJIUzI1NiJ9.payload{n}.signature{n}");
+        let keys: Vec<Vec<u8>> = ["alice", "bob"]
+            .into_iter()
+            .flat_map(|username| {
+                (1..=3).map(move |n| {
+                    AccessTokens::key(&ATokenProps {
+                        username: username.to_string(),
+                        token: token(n),
+                    })
+                })
+            })
+            .collect();
+        let unmask = |masked: &[u8]| {
+            AccessTokens::unmask_key(masked, &mut |from, f| {
+                for key in keys.iter().filter(|key| key.as_slice() >= from) {
+                    if f(key).is_break() {
+                        break;
                     }
                 }
-            }
-        }
+                Ok(())
+            })
+            .unwrap()
+        };
+
+        let masked = AccessTokens::mask_key(&keys[4]);
+        assert!(!String::from_utf8_lossy(&masked).contains(&token(2)));
+        assert_eq!(unmask(&masked), keys[4]);
+
+        // A cursor at a revoked token starts at the first token of the
+        // account.
+        let gone = AccessTokens::mask_key(b"bob\0eyJhbGciOiJIUzI1NiJ9.gone.signature9");
+        assert_eq!(unmask(&gone), b"bob\0");
+    }
+
+    /// Decodes a token the way review-database encodes it.
+    #[test]
+    fn decodes_what_review_database_writes() {
+        let data = tempfile::tempdir().unwrap();
+        let backup = tempfile::tempdir().unwrap();
+        let store = Store::new(data.path(), backup.path()).unwrap();
+        store.access_token_map().insert("alice", "a.b.c").unwrap();
+
+        let db = rocks::Db::open(&data.path().join(rocks::DB_DIR), None).unwrap();
+        let mut keys = Vec::new();
+        db.scan(
+            "access_tokens",
+            review_database::Direction::Forward,
+            None,
+            &mut |key, value| {
+                keys.push((key, value));
+                std::ops::ControlFlow::Continue(())
+            },
+        )
+        .unwrap();
+        assert_eq!(keys.len(), 1);
+        let (key, value) = &keys[0];
+        let decoded = AccessTokens::decode(key, value).unwrap();
+        assert_eq!(decoded.username, "alice");
+        assert_eq!(decoded.token, "a.b.c");
+        assert_eq!(AccessTokens::key(&decoded), *key);
     }
 }
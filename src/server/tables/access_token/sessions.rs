@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use std::ops::ControlFlow;
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use review_database::Direction;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{expiry, format_time, mask, ATokenProps, AccessTokens, TableSpec};
+use crate::server::tables::{Confirm, Entries, Failures, StatusLine};
+#[cfg(feature = "server")]
+use crate::server::{auth::authorize, permission::Access, state::review, State};
+
+/// The tokens issued to one account.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Session {
+    username: String,
+    tokens: Vec<TokenInfo>,
+}
+
+/// A masked token.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TokenInfo {
+    /// Identifies the token in requests without sending it back.
+    id: String,
+    masked: String,
+    expires: Option<String>,
+    expired: bool,
+}
+
+/// Derives the ID of token `token` of account `username` for
+/// [`TokenInfo::id`]: the key the token is stored under, with the token
+/// masked, in hex, as page cursors hold it.
+#[cfg(feature = "server")]
+fn token_id(username: &str, token: &str) -> String {
+    use data_encoding::HEXLOWER;
+
+    HEXLOWER.encode(format!("{username}\0{}", mask(token)).as_bytes())
+}
+
+/// Collects the tokens of account `username`, which `scan` reads from a key
+/// on, and counts the entries among them that fail to decode.
+#[cfg(feature = "server")]
+fn tokens_of(
+    username: &str,
+    scan: impl FnOnce(
+        &[u8],
+        &mut dyn FnMut(anyhow::Result<ATokenProps>) -> ControlFlow<()>,
+    ) -> anyhow::Result<()>,
+) -> anyhow::Result<(Vec<String>, usize)> {
+    let mut from = username.as_bytes().to_vec();
+    from.push(0);
+    let mut tokens = Vec::new();
+    let mut undecodable = 0;
+    scan(&from, &mut |res| {
+        match res {
+            Ok(entry) if entry.username == username => tokens.push(entry.token),
+            Ok(_) => return ControlFlow::Break(()),
+            Err(_) => undecodable += 1,
+        }
+        ControlFlow::Continue(())
+    })?;
+    Ok((tokens, undecodable))
+}
+
+/// Picks the token with ID `id` from `tokens` of account `username`,
+/// refusing an ID more than one of them has.
+#[cfg(feature = "server")]
+fn pick(username: &str, tokens: Vec<String>, id: &str) -> anyhow::Result<String> {
+    let mut found = tokens
+        .into_iter()
+        .filter(|token| token_id(username, token) == id);
+    match (found.next(), found.next()) {
+        (Some(token), None) => Ok(token),
+        (None, _) => Err(anyhow::anyhow!("no such token of {username}")),
+        (Some(_), Some(_)) => Err(anyhow::anyhow!(
+            "more than one token of {username} looks like this one; revoke them all instead"
+        )),
+    }
+}
+
+#[cfg(feature = "server")]
+impl State {
+    /// Reads every access token, and the entries that could not be decoded.
+    async fn tokens(&self) -> anyhow::Result<Entries<ATokenProps>> {
+        let tokens = self.with_source(AccessTokens::read_all).await?;
+        self.mark_read();
+        Ok(tokens)
+    }
+
+    /// Groups the access tokens by account, in the order of the usernames.
+    pub(crate) async fn sessions(&self) -> anyhow::Result<Entries<Session>> {
+        let now = chrono::Utc::now();
+        let tokens = self.tokens().await?;
+        let mut sessions: Vec<Session> = Vec::new();
+        for entry in tokens.rows {
+            let expires = expiry(&entry.token);
+            let token = TokenInfo {
+                id: token_id(&entry.username, &entry.token),
+                masked: mask(&entry.token),
+                expires: expires.map(format_time),
+                expired: expires.is_some_and(|t| t <= now),
+            };
+            match sessions.last_mut() {
+                Some(session) if session.username == entry.username => session.tokens.push(token),
+                _ => sessions.push(Session {
+                    username: entry.username,
+                    tokens: vec![token],
+                }),
+            }
+        }
+        Ok(Entries {
+            rows: sessions,
+            failures: tokens.failures,
+        })
+    }
+
+    /// Returns the token of account `username` with ID `id`.
+    pub(crate) async fn find_token(&self, username: &str, id: &str) -> anyhow::Result<String> {
+        let (username, id) = (username.to_string(), id.to_string());
+        self.with_source(move |source| {
+            let (tokens, _) = tokens_of(&username, |from, f| {
+                AccessTokens::scan(source, Direction::Forward, Some(from), &mut |_, res| f(res))
+            })?;
+            pick(&username, tokens, &id)
+        })
+        .await
+    }
+
+    /// Revokes the token of account `username` with ID `id`, holding the
+    /// store exclusively on a blocking thread.
+    pub(crate) async fn revoke_token(&self, username: &str, id: &str) -> anyhow::Result<()> {
+        self.check_writable()?;
+        let (username, id) = (username.to_string(), id.to_string());
+        self.with_store_mut(move |store| {
+            let (tokens, _) = tokens_of(&username, |from, f| {
+                AccessTokens::iter(store, Direction::Forward, Some(from), f)
+            })?;
+            let token = pick(&username, tokens, &id)?;
+            store.access_token_map().revoke(&username, &token)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Revokes every token of account `username`, holding the store
+    /// exclusively on a blocking thread, and returns how many there were.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, after revoking the rest, if any entry among the
+    /// tokens of the account cannot be decoded and thus revoked.
+    pub(crate) async fn revoke_tokens(&self, username: &str) -> anyhow::Result<usize> {
+        self.check_writable()?;
+        let name = username.to_string();
+        let (revoked, undecodable) = self
+            .with_store_mut(move |store| {
+                let (tokens, undecodable) = tokens_of(&name, |from, f| {
+                    AccessTokens::iter(store, Direction::Forward, Some(from), f)
+                })?;
+                let table = store.access_token_map();
+                for token in &tokens {
+                    table.revoke(&name, token)?;
+                }
+                Ok((tokens.len(), undecodable))
+            })
+            .await?;
+        if undecodable > 0 {
+            return Err(anyhow::anyhow!(
+                "revoked {revoked} tokens of {username}, but {undecodable} entries among them cannot be decoded"
+            ));
+        }
+        Ok(revoked)
+    }
+}
+
+#[server]
+async fn list_sessions(profile: String) -> Result<Entries<Session>, ServerFnError> {
+    authorize(AccessTokens::PERMISSION, Access::Read).await?;
+    let review = review(&profile).await?;
+    review.sessions().await.map_err(ServerFnError::new)
+}
+
+#[server]
+async fn reveal_token(
+    profile: String,
+    username: String,
+    id: String,
+) -> Result<String, ServerFnError> {
+    let user = authorize(AccessTokens::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    let token = review
+        .find_token(&username, &id)
+        .await
+        .map_err(ServerFnError::new)?;
+    dioxus_logger::tracing::info!(
+        "{} revealed a token of {username} in profile {profile}",
+        user.username
+    );
+    Ok(token)
+}
+
+#[server]
+async fn revoke_token(profile: String, username: String, id: String) -> Result<(), ServerFnError> {
+    let user = authorize(AccessTokens::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} revokes a token of {username} in profile {profile}",
+        user.username
+    );
+    review
+        .revoke_token(&username, &id)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+async fn revoke_all_tokens(profile: String, username: String) -> Result<usize, ServerFnError> {
+    let user = authorize(AccessTokens::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} revokes every token of {username} in profile {profile}",
+        user.username
+    );
+    review
+        .revoke_tokens(&username)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+/// Lists the tokens of each account, with actions to reveal and revoke them.
+#[component]
+pub(super) fn Sessions(profile: String, on_change: EventHandler<()>) -> Element {
+    let mut sessions = use_server_future(use_reactive((&profile,), |(profile,)| {
+        list_sessions(profile)
+    }))?;
+    // Revealed tokens by ID.
+    let mut revealed = use_signal(HashMap::<String, String>::new);
+    // The account whose tokens are about to be revoked.
+    let mut revoking = use_signal(|| None::<String>);
+    let mut status = use_signal(|| None::<Result<String, String>>);
+
+    rsx! {
+        section { style: "margin: 16px 0; max-width: 1200px;",
+            h3 { style: "font: small-caps bold 18px sans-serif;", "Sessions" }
+            match sessions() {
+                None => rsx! { p { "Loading..." } },
+                Some(Err(e)) => rsx! { p { "{e}" } },
+                Some(Ok(list)) => rsx! {
+                    Failures { failures: list.failures }
+                    for session in list.rows {
+                        div { style: "margin: 8px 0;",
+                            h4 { style: "font-weight: bold;",
+                                "{session.username} ({session.tokens.len()} tokens) "
+                                button {
+                                    class: "hover:bg-gray-100",
+                                    style: "color: red;",
+                                    onclick: {
+                                        let username = session.username.clone();
+                                        move |_| revoking.set(Some(username.clone()))
+                                    },
+                                    "Revoke all"
+                                }
+                            }
+                            if revoking().as_deref() == Some(session.username.as_str()) {
+                                Confirm {
+                                    on_confirm: {
+                                        let profile = profile.clone();
+                                        let username = session.username.clone();
+                                        move |()| {
+                                            let profile = profile.clone();
+                                            let username = username.clone();
+                                            spawn(async move {
+                                                revoking.set(None);
+                                                match revoke_all_tokens(profile, username.clone()).await {
+                                                    Ok(n) => {
+                                                        status.set(Some(Ok(format!("revoked {n} tokens of {username}"))));
+                                                        sessions.restart();
+                                                        on_change.call(());
+                                                    }
+                                                    Err(e) => status.set(Some(Err(e.to_string()))),
+                                                }
+                                            });
+                                        }
+                                    },
+                                    on_cancel: move |()| revoking.set(None),
+                                    "Revoke every token of {session.username}? They will be signed out everywhere."
+                                }
+                            }
+                            table {
+                                thead {
+                                    tr {
+                                        th { scope: "col", "Token" }
+                                        th { scope: "col", "Expires" }
+                                        th { scope: "col" }
+                                    }
+                                }
+                                tbody {
+                                    for token in session.tokens {
+                                        tr { class: "odd:bg-white even:bg-gray-100",
+                                            td { style: "font-family: monospace; word-break: break-all;",
+                                                match revealed.read().get(&token.id) {
+                                                    Some(full) => rsx! { "{full}" },
+                                                    None => rsx! { "{token.masked}" },
+                                                }
+                                            }
+                                            td {
+                                                {token.expires.clone().unwrap_or_else(|| "unknown".to_string())}
+                                                if token.expired {
+                                                    span { style: "color: darkorange;", " (expired)" }
+                                                }
+                                            }
+                                            td {
+                                                if revealed.read().contains_key(&token.id) {
+                                                    button {
+                                                        class: "hover:bg-gray-100",
+                                                        onclick: {
+                                                            let id = token.id.clone();
+                                                            move |_| {
+                                                                revealed.write().remove(&id);
+                                                            }
+                                                        },
+                                                        "Hide"
+                                                    }
+                                                } else {
+                                                    button {
+                                                        class: "hover:bg-gray-100",
+                                                        onclick: {
+                                                            let profile = profile.clone();
+                                                            let username = session.username.clone();
+                                                            let id = token.id.clone();
+                                                            move |_| {
+                                                                let profile = profile.clone();
+                                                                let username = username.clone();
+                                                                let id = id.clone();
+                                                                async move {
+                                                                    match reveal_token(profile, username, id.clone()).await {
+                                                                        Ok(full) => {
+                                                                            revealed.write().insert(id, full);
+                                                                        }
+                                                                        Err(e) => status.set(Some(Err(e.to_string()))),
+                                                                    }
+                                                                }
+                                                            }
+                                                        },
+                                                        "Reveal"
+                                                    }
+                                                }
+                                                button {
+                                                    class: "hover:bg-gray-100",
+                                                    style: "margin-left: 8px; color: red;",
+                                                    onclick: {
+                                                        let profile = profile.clone();
+                                                        let username = session.username.clone();
+                                                        let id = token.id.clone();
+                                                        move |_| {
+                                                            let profile = profile.clone();
+                                                            let username = username.clone();
+                                                            let id = id.clone();
+                                                            async move {
+                                                                match revoke_token(profile, username.clone(), id).await {
+                                                                    Ok(()) => {
+                                                                        status.set(Some(Ok(format!("revoked a token of {username}"))));
+                                                                        sessions.restart();
+                                                                        on_change.call(());
+                                                                    }
+                                                                    Err(e) => status.set(Some(Err(e.to_string()))),
+                                                                }
+                                                            }
+                                                        }
+                                                    },
+                                                    "Revoke"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+            StatusLine { status: status() }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::{pick, token_id};
+
+    #[test]
+    fn picks_tokens_by_id() {
+        let token =
+            |payload: &str, signature: &str| format!("eyJhbGciOiJIUzI1NiJ9.{payload}.{signature}");
+        let tokens = vec![token("a", "sig1"), token("b", "sig2"), token("c", "sig2")];
+
+        let id = token_id("alice", &tokens[0]);
+        assert_eq!(pick("alice", tokens.clone(), &id).unwrap(), tokens[0]);
+        assert_ne!(token_id("bob", &tokens[0]), id);
+
+        // The last two tokens mask alike, so neither is picked.
+        let id = token_id("alice", &tokens[1]);
+        assert!(pick("alice", tokens.clone(), &id).is_err());
+        assert!(pick("alice", tokens, "00").is_err());
+    }
+}
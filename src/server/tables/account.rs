@@ -1,156 +1,184 @@
+mod manage;
+#[cfg(feature = "server")]
+use std::ops::ControlFlow;
+
 #[cfg(feature = "server")]
 use anyhow::Result;
 use dioxus::prelude::*;
-
 #[cfg(feature = "server")]
-use crate::server::{state::review, State};
+use review_database::{types::Account, Direction, Store};
 
+use super::TableSpec;
 #[cfg(feature = "server")]
-impl State {
-    pub async fn accounts(&self) -> Result<Vec<review_database::types::Account>> {
-        use review_database::{Direction, Iterable};
+use super::{format_time, Data};
+use crate::server::permission::{Permission, MANAGERS};
+#[cfg(feature = "server")]
+use crate::server::{permission::Role, State};
+
+pub(super) struct Accounts;
+
+impl TableSpec for Accounts {
+    const NAME: &'static str = "account";
+    const TITLE: &'static str = "Accounts";
+    const COLUMNS: &'static [&'static str] = &[
+        "User Name",
+        "Name",
+        "Department",
+        "Role",
+        "Language",
+        "Theme",
+        "Created",
+        "Last Sign-in",
+        "Allowed IPs",
+        "Max Sessions",
+        "Password Changed",
+    ];
+    const PERMISSION: Permission = MANAGERS;
+    #[cfg(feature = "server")]
+    const COLUMN_FAMILY: Option<&'static str> = Some("accounts");
+
+    #[cfg(feature = "server")]
+    type Entry = Account;
+
+    #[cfg(feature = "server")]
+    fn iter(
+        store: &Store,
+        direction: Direction,
+        from: Option<&[u8]>,
+        f: &mut dyn FnMut(Result<Account>) -> ControlFlow<()>,
+    ) -> Result<()> {
+        use review_database::Iterable;
 
-        let store = self.store.read().await;
         let table = store.account_map();
-        Ok(table
-            .iter(Direction::Forward, None)
-            .filter_map(|res| match res {
-                Ok(item) => Some(item),
-                Err(e) => {
-                    dioxus_logger::tracing::error!("Error retrieving account: {:?}", e);
-                    None
-                }
-            })
-            .collect())
+        for res in table.iter(direction, from) {
+            if f(res).is_break() {
+                break;
+            }
+        }
+        Ok(())
     }
-}
 
-#[server]
-async fn accounts() -> Result<Vec<String>, ServerFnError> {
-    let review = review().await?;
+    #[cfg(feature = "server")]
+    fn key(entry: &Account) -> Vec<u8> {
+        entry.username.as_bytes().to_vec()
+    }
 
-    review
-        .accounts()
-        .await
-        .map(|entries| {
-            entries
-                .into_iter()
-                .filter_map(|e| serde_json::to_string(&e).ok())
-                .collect()
-        })
-        .map_err(ServerFnError::new)
-}
+    /// Review-database stores an account under its username, encoded with
+    /// bincode.
+    #[cfg(feature = "server")]
+    fn decode(_key: &[u8], value: &[u8]) -> Result<Account> {
+        use bincode::Options;
 
-#[component]
-fn Account(entry: String) -> Element {
-    rsx! {
-        p { "{entry}" }
+        Ok(bincode::DefaultOptions::new().deserialize::<Account>(value)?)
     }
-}
 
-#[component]
-pub fn Digest() -> Element {
-    let entries = use_server_future(accounts)?;
-    rsx! {
-        tr {
-            th { style: "width: 200px; text-align: right;", scope: "row",
-                Link {
-                    class: "hover:bg-gray-100",
-                    to: crate::Route::Table {
-                        name: super::LookUp::Account.to_string(),
-                    },
-                    "Account"
-                }
-            }
-            match entries() {
-                None => rsx!{td { colspan: 2, "Loading..." }},
-                Some(Err(e)) => rsx!{td {colspan: 2, "{e}"}},
-                Some(Ok(entries)) => rsx!{
-                    td { style: "width: 100px; text-align: center;", "{entries.len()}" }
-                    td {
-                        ol {
-                            for entry in entries.into_iter().take(3) {
-                                li {
-                                    Account { entry: entry }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    /// Leaves out the password hash.
+    #[cfg(feature = "server")]
+    fn cells(entry: &Account) -> Vec<String> {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        vec![
+            entry.username.clone(),
+            entry.name.clone(),
+            entry.department.clone(),
+            Role::from(entry.role).to_string(),
+            optional(&entry.language),
+            optional(&entry.theme),
+            format_time(entry.creation_time()),
+            entry
+                .last_signin_time()
+                .map(format_time)
+                .unwrap_or_default(),
+            entry.allow_access_from.as_ref().map_or_else(
+                || "any".to_string(),
+                |ips| {
+                    ips.iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            ),
+            entry
+                .max_parallel_sessions
+                .map_or_else(|| "unlimited".to_string(), |n| n.to_string()),
+            format_time(entry.password_last_modified_at()),
+        ]
     }
-}
 
-#[component]
-fn Columns(entry: String) -> Element {
-    let entry: serde_json::Value = serde_json::from_str(&entry).ok()?;
-
-    if let serde_json::Value::Object(entry) = entry {
-        let mut entry: Vec<_> = entry.into_iter().map(|(k, _v)| k).collect();
-        entry.sort_unstable();
-        rsx! {
-            tr {
-                for k in entry {
-                    th { scope: "col", "{k}" }
-                }
-            }
+    /// Redacts the password hash.
+    #[cfg(feature = "server")]
+    fn dump(entry: &Account) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(entry)?;
+        if let Some(password) = value.get_mut("password") {
+            *password = "<redacted>".into();
         }
-    } else {
-        rsx! {}
+        Ok(value)
+    }
+
+    /// Redacts the password hash the way [`TableSpec::dump`] does.
+    #[cfg(feature = "server")]
+    fn decode_value(value: &[u8]) -> Option<Result<String>> {
+        Some(
+            Self::decode(&[], value)
+                .and_then(|account| Self::dump(&account))
+                .map(|value| value.to_string()),
+        )
     }
 }
 
-#[component]
-fn Row(entry: String) -> Element {
-    let entry: serde_json::Value = serde_json::from_str(&entry).ok()?;
-    if let serde_json::Value::Object(entry) = entry {
-        let mut entry: Vec<_> = entry.into_iter().collect();
-        entry.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        rsx! {
-            tr {
-                class: "odd:bg-white even:bg-slate-50",
-                for (_k , v) in entry {
-                    td { "{v}" }
-                }
-            }
-        }
-    } else {
-        rsx! {
-            tr {
-                td { "{entry}" }
-            }
-        }
+#[cfg(feature = "server")]
+impl State {
+    /// Reads account `username` from the store, or, in read-only mode, from
+    /// the secondary instance.
+    pub(crate) async fn find_account(&self, username: &str) -> Result<Option<Account>> {
+        let username = username.to_string();
+        self.with_source(move |source| match source.data {
+            Data::Store(store) => store.account_map().get(&username),
+            Data::Secondary(db) => db
+                .get("accounts", username.as_bytes())?
+                .map(|value| Accounts::decode(username.as_bytes(), &value))
+                .transpose(),
+        })
+        .await
     }
 }
 
-#[component]
-pub(crate) fn Full() -> Element {
-    let entries = use_server_future(accounts)?;
-    rsx! {
-        table { style: "table-layout: fixed;
-                max-width: 1200px; max-height: 1200px;
-                overflow: auto; display: block;
-                border-spacing: 0;",
-            caption { style: "font: small-caps bold 24px sans-serif; text-align: center; border-bottom: 1px solid rgba(0, 0, 0, 0.5)",
-                "account"
-            }
-            thead {
-                match entries() {
-                    None => rsx!{td {colspan: 2, "Loading..."}},
-                    Some(Err(e)) => rsx!{td {colspan: 2, "{e}"}},
-                    Some(Ok(entries)) => {
-                        rsx!{
-                            if let Some(entry) = entries.first() {
-                                Columns{ entry }
-                            }
-                            for entry in entries.into_iter() {
-                                Row { entry }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use review_database::{
+        types::{Account, Role},
+        Store,
+    };
+
+    use super::Accounts;
+    use crate::server::{rocks, tables::TableSpec};
+
+    /// Decodes an account the way review-database encodes it.
+    #[test]
+    fn decodes_what_review_database_writes() {
+        let data = tempfile::tempdir().unwrap();
+        let backup = tempfile::tempdir().unwrap();
+        let store = Store::new(data.path(), backup.path()).unwrap();
+        let account = Account::new(
+            "alice",
+            "password",
+            Role::SecurityManager,
+            "Alice".to_string(),
+            "Security".to_string(),
+            Some("en".to_string()),
+            None,
+            Some(vec!["127.0.0.1".parse().unwrap()]),
+            Some(3),
+        )
+        .unwrap();
+        store.account_map().insert(&account).unwrap();
+        let stored = store.account_map().get("alice").unwrap().unwrap();
+
+        let db = rocks::Db::open(&data.path().join(rocks::DB_DIR), None).unwrap();
+        let value = db.get("accounts", b"alice").unwrap().unwrap();
+        let decoded = Accounts::decode(b"alice", &value).unwrap();
+        assert_eq!(
+            serde_json::to_value(decoded).unwrap(),
+            serde_json::to_value(stored).unwrap()
+        );
     }
 }
@@ -0,0 +1,689 @@
+use std::str::FromStr;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+#[cfg(feature = "server")]
+use super::{Accounts, TableSpec};
+#[cfg(feature = "server")]
+use crate::server::{auth::authorize, permission::Access, state::review, State};
+use crate::server::{
+    permission::Role,
+    tables::{Confirm, StatusLine},
+};
+
+/// The fields of an account the forms set, as entered.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AccountFields {
+    role: Role,
+    name: String,
+    department: String,
+    /// Unset if empty.
+    language: String,
+    /// Unset if empty.
+    theme: String,
+    /// IP addresses separated by commas or whitespace; any address may sign
+    /// in if empty.
+    allow_access_from: String,
+    /// Unlimited if empty.
+    max_parallel_sessions: String,
+}
+
+impl Default for AccountFields {
+    fn default() -> Self {
+        Self {
+            role: Role::SecurityMonitor,
+            name: String::new(),
+            department: String::new(),
+            language: String::new(),
+            theme: String::new(),
+            allow_access_from: String::new(),
+            max_parallel_sessions: String::new(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+mod fields {
+    use std::net::IpAddr;
+
+    use anyhow::{anyhow, Context, Result};
+    use review_database::types::{Account, Role};
+
+    use super::AccountFields;
+
+    /// [`AccountFields`] as review-database takes them.
+    pub(super) struct Parsed {
+        pub(super) role: Role,
+        pub(super) name: String,
+        pub(super) department: String,
+        pub(super) language: Option<String>,
+        pub(super) theme: Option<String>,
+        pub(super) allow_access_from: Option<Vec<IpAddr>>,
+        pub(super) max_parallel_sessions: Option<u8>,
+    }
+
+    fn optional(value: String) -> Option<String> {
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    impl AccountFields {
+        pub(super) fn parse(self) -> Result<Parsed> {
+            let ips: Vec<IpAddr> = self
+                .allow_access_from
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|ip| !ip.is_empty())
+                .map(|ip| {
+                    ip.parse()
+                        .with_context(|| format!("invalid IP address {ip}"))
+                })
+                .collect::<Result<_>>()?;
+            let max_parallel_sessions = optional(self.max_parallel_sessions)
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| anyhow!("max sessions must be a number up to 255"))
+                })
+                .transpose()?;
+            Ok(Parsed {
+                role: self.role.into(),
+                name: self.name.trim().to_string(),
+                department: self.department.trim().to_string(),
+                language: optional(self.language),
+                theme: optional(self.theme),
+                allow_access_from: (!ips.is_empty()).then_some(ips),
+                max_parallel_sessions,
+            })
+        }
+
+        pub(super) fn from_account(account: &Account) -> Self {
+            Self {
+                role: account.role.into(),
+                name: account.name.clone(),
+                department: account.department.clone(),
+                language: account.language.clone().unwrap_or_default(),
+                theme: account.theme.clone().unwrap_or_default(),
+                allow_access_from: account
+                    .allow_access_from
+                    .as_ref()
+                    .map(|ips| {
+                        ips.iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default(),
+                max_parallel_sessions: account
+                    .max_parallel_sessions
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl State {
+    /// Adds an account, with `password` hashed the way REview does, holding
+    /// the store exclusively on a blocking thread, where the hashing runs
+    /// too.
+    pub(crate) async fn create_account(
+        &self,
+        username: &str,
+        password: &str,
+        fields: AccountFields,
+    ) -> anyhow::Result<()> {
+        use anyhow::anyhow;
+        use review_database::types::Account;
+
+        self.check_writable()?;
+        if username.is_empty() || password.is_empty() {
+            return Err(anyhow!("username and password are required"));
+        }
+        let fields = fields.parse()?;
+        let (username, password) = (username.to_string(), password.to_string());
+        self.with_store_mut(move |store| {
+            let account = Account::new(
+                &username,
+                &password,
+                fields.role,
+                fields.name,
+                fields.department,
+                fields.language,
+                fields.theme,
+                fields.allow_access_from,
+                fields.max_parallel_sessions,
+            )?;
+            store.account_map().insert(&account)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn account(&self, username: &str) -> anyhow::Result<review_database::types::Account> {
+        let account = self.find_account(username).await?;
+        account.ok_or_else(|| anyhow::anyhow!("no account named {username}"))
+    }
+
+    /// Changes account `username` with `f`, holding the store exclusively
+    /// from reading the account to writing it back so no other change is
+    /// lost in between. Refuses to demote the last System Administrator.
+    async fn modify_account(
+        &self,
+        username: &str,
+        f: impl FnOnce(&mut review_database::types::Account) -> anyhow::Result<()> + Send + 'static,
+    ) -> anyhow::Result<()> {
+        self.check_writable()?;
+        let username = username.to_string();
+        self.with_store_mut(move |store| {
+            let table = store.account_map();
+            let mut account = table
+                .get(&username)?
+                .ok_or_else(|| anyhow::anyhow!("no account named {username}"))?;
+            let was_administrator = is_system_administrator(&account);
+            f(&mut account)?;
+            if was_administrator && !is_system_administrator(&account) {
+                check_other_administrator(store, &username)?;
+            }
+            table.put(&account)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replaces the fields of account `username` that the forms set.
+    pub(crate) async fn update_account(
+        &self,
+        username: &str,
+        fields: AccountFields,
+    ) -> anyhow::Result<()> {
+        let fields = fields.parse()?;
+        self.modify_account(username, move |account| {
+            account.role = fields.role;
+            account.name = fields.name;
+            account.department = fields.department;
+            account.language = fields.language;
+            account.theme = fields.theme;
+            account.allow_access_from = fields.allow_access_from;
+            account.max_parallel_sessions = fields.max_parallel_sessions;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Sets the password of account `username`, hashed the way REview does.
+    pub(crate) async fn reset_password(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> anyhow::Result<()> {
+        if password.is_empty() {
+            return Err(anyhow::anyhow!("the password is required"));
+        }
+        let password = password.to_string();
+        self.modify_account(username, move |account| {
+            account.update_password(&password)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Deletes account `username` and revokes its access tokens, returning
+    /// how many there were. Refuses to delete the last System Administrator.
+    pub(crate) async fn delete_account(&self, username: &str) -> anyhow::Result<usize> {
+        use anyhow::Context;
+
+        self.check_writable()?;
+        let name = username.to_string();
+        self.with_store_mut(move |store| {
+            let table = store.account_map();
+            let account = table
+                .get(&name)?
+                .ok_or_else(|| anyhow::anyhow!("no account named {name}"))?;
+            if is_system_administrator(&account) {
+                check_other_administrator(store, &name)?;
+            }
+            table.delete(&name)?;
+            Ok(())
+        })
+        .await?;
+        self.revoke_tokens(username)
+            .await
+            .with_context(|| format!("deleted account {username}"))
+    }
+}
+
+#[cfg(feature = "server")]
+fn is_system_administrator(account: &review_database::types::Account) -> bool {
+    matches!(
+        account.role,
+        review_database::types::Role::SystemAdministrator
+    )
+}
+
+/// Fails unless an account other than `username` is a System Administrator,
+/// so that the last one is neither deleted nor demoted.
+#[cfg(feature = "server")]
+fn check_other_administrator(store: &review_database::Store, username: &str) -> anyhow::Result<()> {
+    use review_database::{Direction, Iterable};
+
+    for account in store.account_map().iter(Direction::Forward, None) {
+        let account = account?;
+        if account.username != username && is_system_administrator(&account) {
+            return Ok(());
+        }
+    }
+    Err(anyhow::anyhow!(
+        "{username} is the last System Administrator"
+    ))
+}
+
+#[server]
+async fn create_account(
+    profile: String,
+    username: String,
+    password: String,
+    fields: AccountFields,
+) -> Result<(), ServerFnError> {
+    let user = authorize(Accounts::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} creates account {username} in profile {profile}",
+        user.username
+    );
+    review
+        .create_account(&username, &password, fields)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+async fn account_fields(profile: String, username: String) -> Result<AccountFields, ServerFnError> {
+    authorize(Accounts::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    review
+        .account(&username)
+        .await
+        .map(|account| AccountFields::from_account(&account))
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+async fn update_account(
+    profile: String,
+    username: String,
+    fields: AccountFields,
+) -> Result<(), ServerFnError> {
+    let user = authorize(Accounts::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} updates account {username} in profile {profile}",
+        user.username
+    );
+    review
+        .update_account(&username, fields)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+async fn reset_password(
+    profile: String,
+    username: String,
+    password: String,
+) -> Result<(), ServerFnError> {
+    let user = authorize(Accounts::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} resets the password of account {username} in profile {profile}",
+        user.username
+    );
+    review
+        .reset_password(&username, &password)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+async fn delete_account(profile: String, username: String) -> Result<usize, ServerFnError> {
+    let user = authorize(Accounts::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} deletes account {username} in profile {profile}",
+        user.username
+    );
+    review
+        .delete_account(&username)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[component]
+fn FieldsEditor(fields: Signal<AccountFields>) -> Element {
+    let mut fields = fields;
+    let current = fields();
+    rsx! {
+        label { style: "display: flex; flex-direction: column;",
+            "Role"
+            select {
+                onchange: move |e| {
+                    if let Ok(role) = Role::from_str(&e.value()) {
+                        fields.write().role = role;
+                    }
+                },
+                for role in Role::iter() {
+                    option { value: "{role}", selected: current.role == role, "{role}" }
+                }
+            }
+        }
+        label { style: "display: flex; flex-direction: column;",
+            "Name"
+            input {
+                r#type: "text",
+                value: "{current.name}",
+                oninput: move |e| fields.write().name = e.value()
+            }
+        }
+        label { style: "display: flex; flex-direction: column;",
+            "Department"
+            input {
+                r#type: "text",
+                value: "{current.department}",
+                oninput: move |e| fields.write().department = e.value()
+            }
+        }
+        label { style: "display: flex; flex-direction: column;",
+            "Language"
+            input {
+                r#type: "text",
+                placeholder: "unset",
+                value: "{current.language}",
+                oninput: move |e| fields.write().language = e.value()
+            }
+        }
+        label { style: "display: flex; flex-direction: column;",
+            "Theme"
+            input {
+                r#type: "text",
+                placeholder: "unset",
+                value: "{current.theme}",
+                oninput: move |e| fields.write().theme = e.value()
+            }
+        }
+        label { style: "display: flex; flex-direction: column;",
+            "Allowed IPs"
+            input {
+                r#type: "text",
+                placeholder: "any",
+                value: "{current.allow_access_from}",
+                oninput: move |e| fields.write().allow_access_from = e.value()
+            }
+        }
+        label { style: "display: flex; flex-direction: column;",
+            "Max Sessions"
+            input {
+                r#type: "text",
+                placeholder: "unlimited",
+                value: "{current.max_parallel_sessions}",
+                oninput: move |e| fields.write().max_parallel_sessions = e.value()
+            }
+        }
+    }
+}
+
+#[component]
+fn CreateForm(profile: String, on_change: EventHandler<()>) -> Element {
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut fields = use_signal(AccountFields::default);
+    let mut status = use_signal(|| None::<Result<String, String>>);
+    rsx! {
+        form {
+            style: "display: flex; flex-direction: column; gap: 8px; max-width: 480px;",
+            prevent_default: "onsubmit",
+            onsubmit: move |_| {
+                let profile = profile.clone();
+                async move {
+                    let name = username();
+                    match create_account(profile, name.clone(), password(), fields()).await {
+                        Ok(()) => {
+                            status.set(Some(Ok(format!("created account {name}"))));
+                            username.set(String::new());
+                            password.set(String::new());
+                            fields.set(AccountFields::default());
+                            on_change.call(());
+                        }
+                        Err(e) => status.set(Some(Err(e.to_string()))),
+                    }
+                }
+            },
+            h3 { style: "font: small-caps bold 18px sans-serif;", "Create an Account" }
+            input {
+                r#type: "text",
+                placeholder: "Username",
+                autocomplete: "off",
+                value: "{username}",
+                oninput: move |e| username.set(e.value())
+            }
+            input {
+                r#type: "password",
+                placeholder: "Password",
+                autocomplete: "new-password",
+                value: "{password}",
+                oninput: move |e| password.set(e.value())
+            }
+            FieldsEditor { fields }
+            button { class: "hover:bg-gray-100", r#type: "submit", "Create" }
+            StatusLine { status: status() }
+        }
+    }
+}
+
+/// An action that waits for confirmation.
+#[derive(Clone, Copy, PartialEq)]
+enum Pending {
+    ResetPassword,
+    Delete,
+}
+
+#[component]
+fn EditForm(profile: String, on_change: EventHandler<()>) -> Element {
+    let mut username = use_signal(String::new);
+    // The account the fields were loaded from.
+    let mut loaded = use_signal(|| None::<String>);
+    let fields = use_signal(AccountFields::default);
+    let mut password = use_signal(String::new);
+    let mut pending = use_signal(|| None::<Pending>);
+    let mut status = use_signal(|| None::<Result<String, String>>);
+
+    let load = {
+        let profile = profile.clone();
+        move |_| {
+            let profile = profile.clone();
+            let mut fields = fields;
+            async move {
+                let name = username();
+                match account_fields(profile, name.clone()).await {
+                    Ok(found) => {
+                        fields.set(found);
+                        loaded.set(Some(name));
+                        status.set(None);
+                    }
+                    Err(e) => {
+                        loaded.set(None);
+                        status.set(Some(Err(e.to_string())));
+                    }
+                }
+                pending.set(None);
+            }
+        }
+    };
+    let save = {
+        let profile = profile.clone();
+        move |_| {
+            let profile = profile.clone();
+            async move {
+                let Some(name) = loaded() else {
+                    return;
+                };
+                match update_account(profile, name.clone(), fields()).await {
+                    Ok(()) => {
+                        status.set(Some(Ok(format!("updated account {name}"))));
+                        on_change.call(());
+                    }
+                    Err(e) => status.set(Some(Err(e.to_string()))),
+                }
+            }
+        }
+    };
+    let confirm = move |_| {
+        let profile = profile.clone();
+        async move {
+            let (Some(name), Some(action)) = (loaded(), pending()) else {
+                return;
+            };
+            pending.set(None);
+            let result = match action {
+                Pending::ResetPassword => reset_password(profile, name.clone(), password())
+                    .await
+                    .map(|()| format!("reset the password of {name}")),
+                Pending::Delete => delete_account(profile, name.clone())
+                    .await
+                    .map(|n| format!("deleted account {name} and revoked its {n} tokens")),
+            };
+            match result {
+                Ok(message) => {
+                    status.set(Some(Ok(message)));
+                    password.set(String::new());
+                    if action == Pending::Delete {
+                        loaded.set(None);
+                    }
+                    on_change.call(());
+                }
+                Err(e) => status.set(Some(Err(e.to_string()))),
+            }
+        }
+    };
+
+    rsx! {
+        div { style: "display: flex; flex-direction: column; gap: 8px; max-width: 480px;",
+            h3 { style: "font: small-caps bold 18px sans-serif;", "Edit an Account" }
+            form {
+                style: "display: flex; flex-direction: row; gap: 8px;",
+                prevent_default: "onsubmit",
+                onsubmit: load,
+                input {
+                    r#type: "text",
+                    placeholder: "Username",
+                    autocomplete: "off",
+                    value: "{username}",
+                    oninput: move |e| username.set(e.value())
+                }
+                button { class: "hover:bg-gray-100", r#type: "submit", "Load" }
+            }
+            if let Some(name) = loaded() {
+                form {
+                    style: "display: flex; flex-direction: column; gap: 8px;",
+                    prevent_default: "onsubmit",
+                    onsubmit: save,
+                    FieldsEditor { fields }
+                    button { class: "hover:bg-gray-100", r#type: "submit", "Save {name}" }
+                }
+                div { style: "display: flex; flex-direction: row; gap: 8px;",
+                    input {
+                        r#type: "password",
+                        placeholder: "New password",
+                        autocomplete: "new-password",
+                        value: "{password}",
+                        oninput: move |e| password.set(e.value())
+                    }
+                    button {
+                        class: "hover:bg-gray-100",
+                        onclick: move |_| pending.set(Some(Pending::ResetPassword)),
+                        "Reset password"
+                    }
+                    button {
+                        class: "hover:bg-gray-100",
+                        style: "color: red;",
+                        onclick: move |_| pending.set(Some(Pending::Delete)),
+                        "Delete"
+                    }
+                }
+                if let Some(action) = pending() {
+                    Confirm {
+                        on_confirm: move |()| {
+                            spawn(confirm(()));
+                        },
+                        on_cancel: move |()| pending.set(None),
+                        match action {
+                            Pending::ResetPassword => rsx! { "Reset the password of {name}? They will have to sign in with the new one." },
+                            Pending::Delete => rsx! { "Delete account {name} and revoke its access tokens? This cannot be undone." },
+                        }
+                    }
+                }
+            }
+            StatusLine { status: status() }
+        }
+    }
+}
+
+/// The forms that create and change accounts.
+///
+/// There is no form to lock an account: review-database's `Account` has no
+/// locked or disabled state to set.
+#[component]
+pub(super) fn Manage(profile: String, on_change: EventHandler<()>) -> Element {
+    rsx! {
+        section { style: "display: flex; flex-direction: row; flex-wrap: wrap; gap: 32px; margin: 16px 0;",
+            CreateForm { profile: profile.clone(), on_change }
+            EditForm { profile, on_change }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use review_database::{
+        types::{Account, Role},
+        Store,
+    };
+
+    use super::check_other_administrator;
+
+    fn account(username: &str, role: Role) -> Account {
+        Account::new(
+            username,
+            "password",
+            role,
+            String::new(),
+            String::new(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn keeps_a_system_administrator() {
+        let data = tempfile::tempdir().unwrap();
+        let backup = tempfile::tempdir().unwrap();
+        let store = Store::new(data.path(), backup.path()).unwrap();
+        let table = store.account_map();
+        table
+            .insert(&account("alice", Role::SystemAdministrator))
+            .unwrap();
+        table
+            .insert(&account("bob", Role::SecurityAdministrator))
+            .unwrap();
+        assert!(check_other_administrator(&store, "alice").is_err());
+        assert!(check_other_administrator(&store, "bob").is_ok());
+
+        table
+            .insert(&account("carol", Role::SystemAdministrator))
+            .unwrap();
+        assert!(check_other_administrator(&store, "alice").is_ok());
+    }
+}
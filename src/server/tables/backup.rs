@@ -0,0 +1,136 @@
+mod manage;
+mod schedule;
+#[cfg(feature = "server")]
+use std::ops::ControlFlow;
+
+#[cfg(feature = "server")]
+use anyhow::Result;
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use review_database::Direction;
+#[cfg(feature = "server")]
+use serde::Serialize;
+
+use super::TableSpec;
+#[cfg(feature = "server")]
+use super::{format_time, Entries, Source};
+use crate::server::permission::{Permission, EVERYONE};
+#[cfg(feature = "server")]
+use crate::server::{rocks, State};
+
+pub(super) struct Backups;
+
+impl TableSpec for Backups {
+    const NAME: &'static str = "backup";
+    const TITLE: &'static str = "Backups";
+    const COLUMNS: &'static [&'static str] = &["ID", "Size", "Number of files", "Time"];
+    const PERMISSION: Permission = EVERYONE;
+    /// Backups live in the backup engine rather than a column family.
+    #[cfg(feature = "server")]
+    const COLUMN_FAMILY: Option<&'static str> = None;
+
+    #[cfg(feature = "server")]
+    type Entry = BackupEngineInfoProps;
+
+    /// Scans the backups in the order of their IDs, reading each from its
+    /// meta file.
+    #[cfg(feature = "server")]
+    fn scan(
+        source: &Source,
+        direction: Direction,
+        from: Option<&[u8]>,
+        f: &mut dyn FnMut(Option<Vec<u8>>, Result<BackupEngineInfoProps>) -> ControlFlow<()>,
+    ) -> Result<()> {
+        let dir = source.backup_dir.join(rocks::DB_DIR);
+        let mut ids = rocks::backup_ids(&dir)?;
+        if matches!(direction, Direction::Reverse) {
+            ids.reverse();
+        }
+        for id in ids {
+            let key = backup_key(id);
+            let started = match (from, direction) {
+                (None, _) => true,
+                (Some(from), Direction::Forward) => key.as_slice() >= from,
+                (Some(from), Direction::Reverse) => key.as_slice() <= from,
+            };
+            if !started {
+                continue;
+            }
+            let backup = rocks::BackupMeta::read(&dir, id).and_then(|meta| {
+                Ok(BackupEngineInfoProps {
+                    timestamp: meta.timestamp,
+                    backup_id: id,
+                    size: meta.size(&dir)?,
+                    num_files: u32::try_from(meta.files.len())?,
+                })
+            });
+            if f(Some(key), backup).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "server")]
+    fn key(entry: &BackupEngineInfoProps) -> Vec<u8> {
+        backup_key(entry.backup_id)
+    }
+
+    /// Counts the meta files of the backups rather than reading them.
+    #[cfg(feature = "server")]
+    fn count(source: &Source) -> Option<Result<u64>> {
+        let ids = rocks::backup_ids(&source.backup_dir.join(rocks::DB_DIR));
+        Some(ids.map(|ids| ids.len() as u64))
+    }
+
+    #[cfg(feature = "server")]
+    fn cells(entry: &BackupEngineInfoProps) -> Vec<String> {
+        vec![
+            entry.backup_id.to_string(),
+            entry.size.to_string(),
+            entry.num_files.to_string(),
+            chrono::DateTime::from_timestamp(entry.timestamp, 0)
+                .map_or_else(|| entry.timestamp.to_string(), format_time),
+        ]
+    }
+
+    /// Shows the backup schedule to everyone who may list the backups.
+    fn status(profile: String) -> Element {
+        rsx! {
+            schedule::Schedule { profile }
+        }
+    }
+
+    fn manage(profile: String, on_change: EventHandler<()>) -> Element {
+        rsx! {
+            manage::Manage { profile, on_change }
+        }
+    }
+}
+
+/// Keys backup `id` by its big-endian bytes so keys sort the way IDs do.
+#[cfg(feature = "server")]
+fn backup_key(id: u32) -> Vec<u8> {
+    id.to_be_bytes().to_vec()
+}
+
+#[cfg(feature = "server")]
+#[derive(Serialize)]
+pub struct BackupEngineInfoProps {
+    /// Timestamp of the backup
+    pub timestamp: i64,
+    /// ID of the backup
+    pub backup_id: u32,
+    /// Size of the backup
+    pub size: u64,
+    /// Number of files related to the backup
+    pub num_files: u32,
+}
+
+#[cfg(feature = "server")]
+impl State {
+    /// Lists the backups, reading them from their meta files.
+    pub async fn backups(&self) -> Result<Entries<BackupEngineInfoProps>> {
+        self.with_source(Backups::read_all).await
+    }
+}
@@ -0,0 +1,256 @@
+use dioxus::prelude::*;
+
+#[cfg(feature = "server")]
+use super::{Backups, TableSpec};
+use crate::server::tables::{Confirm, StatusLine};
+#[cfg(feature = "server")]
+use crate::server::{auth::authorize, permission::Access, state::review};
+
+#[server]
+async fn backup_now(profile: String, keep: Option<u32>) -> Result<(), ServerFnError> {
+    let user = authorize(Backups::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!("{} backs up profile {profile}", user.username);
+    review
+        .create_backup(keep.unwrap_or(u32::MAX))
+        .await
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+async fn restore_backup(profile: String, id: u32) -> Result<(), ServerFnError> {
+    let user = authorize(Backups::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} restores backup {id} of profile {profile}",
+        user.username
+    );
+    review.restore_backup(id).await.map_err(ServerFnError::new)
+}
+
+#[server]
+async fn purge_backups(profile: String, keep: u32) -> Result<(), ServerFnError> {
+    let user = authorize(Backups::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    dioxus_logger::tracing::info!(
+        "{} purges all but {keep} backups of profile {profile}",
+        user.username
+    );
+    review.purge_backups(keep).await.map_err(ServerFnError::new)
+}
+
+/// Opens backup `id` read-only as a profile of its own, returning its name.
+#[server]
+async fn browse_backup(profile: String, id: u32) -> Result<String, ServerFnError> {
+    use axum::Extension;
+
+    // Browsing restores the backup into a scratch directory.
+    let user = authorize(Backups::PERMISSION, Access::Write).await?;
+    let Extension(profiles): Extension<crate::server::Profiles> = extract().await?;
+    dioxus_logger::tracing::info!("{} browses backup {id} of profile {profile}", user.username);
+    profiles
+        .browse(&profile, id)
+        .await
+        .map_err(ServerFnError::new)
+}
+
+/// Returns whether each backup passed verification, by ID.
+#[server]
+async fn verify_backups(profile: String) -> Result<Vec<(u32, Result<(), String>)>, ServerFnError> {
+    // Verification reads every file of every backup.
+    authorize(Backups::PERMISSION, Access::Write).await?;
+    let review = review(&profile).await?;
+    let verified = review.verify_backups().await.map_err(ServerFnError::new)?;
+    Ok(verified
+        .into_iter()
+        .map(|(id, res)| (id, res.map_err(|e| format!("{e:#}"))))
+        .collect())
+}
+
+/// An action that waits for confirmation.
+#[derive(Clone, Copy, PartialEq)]
+enum Pending {
+    Restore(u32),
+    Purge(u32),
+}
+
+/// Parses an optional number of backups to keep.
+fn parse_keep(keep: &str) -> Result<Option<u32>, String> {
+    let keep = keep.trim();
+    if keep.is_empty() {
+        return Ok(None);
+    }
+    keep.parse()
+        .map(Some)
+        .map_err(|_| format!("invalid number of backups: {keep}"))
+}
+
+/// The buttons that create, restore, purge, verify and browse backups, with
+/// the result of the last action.
+#[component]
+pub(super) fn Manage(profile: String, on_change: EventHandler<()>) -> Element {
+    let mut keep = use_signal(String::new);
+    let mut restore_id = use_signal(String::new);
+    let mut pending = use_signal(|| None::<Pending>);
+    let mut status = use_signal(|| None::<Result<String, String>>);
+    let mut verified = use_signal(Vec::<(u32, Result<(), String>)>::new);
+    let navigator = use_navigator();
+
+    let create = {
+        let profile = profile.clone();
+        move |_| {
+            let profile = profile.clone();
+            async move {
+                let keep = match parse_keep(&keep()) {
+                    Ok(keep) => keep,
+                    Err(e) => return status.set(Some(Err(e))),
+                };
+                status.set(Some(Ok("backing up...".to_string())));
+                match backup_now(profile, keep).await {
+                    Ok(()) => {
+                        status.set(Some(Ok("backup created".to_string())));
+                        on_change.call(());
+                    }
+                    Err(e) => status.set(Some(Err(e.to_string()))),
+                }
+            }
+        }
+    };
+    let verify = {
+        let profile = profile.clone();
+        move |_| {
+            let profile = profile.clone();
+            async move {
+                status.set(Some(Ok("verifying...".to_string())));
+                match verify_backups(profile).await {
+                    Ok(results) => {
+                        let failed = results.iter().filter(|(_, res)| res.is_err()).count();
+                        status.set(Some(if failed == 0 {
+                            Ok(format!("all {} backups are intact", results.len()))
+                        } else {
+                            Err(format!("{failed} of {} backups are damaged", results.len()))
+                        }));
+                        verified.set(results);
+                    }
+                    Err(e) => status.set(Some(Err(e.to_string()))),
+                }
+            }
+        }
+    };
+    let confirm = {
+        let profile = profile.clone();
+        move |_| {
+            let profile = profile.clone();
+            async move {
+                let Some(action) = pending() else {
+                    return;
+                };
+                pending.set(None);
+                let result = match action {
+                    Pending::Restore(id) => restore_backup(profile, id)
+                        .await
+                        .map(|()| format!("restored backup {id} and reopened the store")),
+                    Pending::Purge(n) => purge_backups(profile, n)
+                        .await
+                        .map(|()| format!("kept the newest {n} backups")),
+                };
+                match result {
+                    Ok(message) => {
+                        status.set(Some(Ok(message)));
+                        on_change.call(());
+                    }
+                    Err(e) => status.set(Some(Err(e.to_string()))),
+                }
+            }
+        }
+    };
+
+    rsx! {
+        section { style: "display: flex; flex-direction: column; gap: 8px; margin: 16px 0; max-width: 640px;",
+            h3 { style: "font: small-caps bold 18px sans-serif;", "Manage Backups" }
+            div { style: "display: flex; flex-direction: row; gap: 8px;",
+                input {
+                    r#type: "text",
+                    placeholder: "Backups to keep",
+                    value: "{keep}",
+                    oninput: move |e| keep.set(e.value())
+                }
+                button { class: "hover:bg-gray-100", onclick: create, "Back up now" }
+                button {
+                    class: "hover:bg-gray-100",
+                    onclick: move |_| match parse_keep(&keep()) {
+                        Ok(Some(n)) => pending.set(Some(Pending::Purge(n))),
+                        Ok(None) => status.set(Some(Err("enter how many backups to keep".to_string()))),
+                        Err(e) => status.set(Some(Err(e))),
+                    },
+                    "Purge"
+                }
+                button { class: "hover:bg-gray-100", onclick: verify, "Verify" }
+            }
+            div { style: "display: flex; flex-direction: row; gap: 8px;",
+                input {
+                    r#type: "text",
+                    placeholder: "Backup ID",
+                    value: "{restore_id}",
+                    oninput: move |e| restore_id.set(e.value())
+                }
+                button {
+                    class: "hover:bg-gray-100",
+                    style: "color: red;",
+                    onclick: move |_| match restore_id().trim().parse() {
+                        Ok(id) => pending.set(Some(Pending::Restore(id))),
+                        Err(_) => status.set(Some(Err("enter the ID of a backup".to_string()))),
+                    },
+                    "Restore"
+                }
+                button {
+                    class: "hover:bg-gray-100",
+                    title: "Restore the backup into a scratch directory and open it read-only",
+                    onclick: move |_| {
+                        let profile = profile.clone();
+                        async move {
+                            let Ok(id) = restore_id().trim().parse() else {
+                                return status.set(Some(Err("enter the ID of a backup".to_string())));
+                            };
+                            status.set(Some(Ok(format!("restoring backup {id} for browsing..."))));
+                            match browse_backup(profile, id).await {
+                                Ok(name) => {
+                                    navigator.push(crate::Route::Home { profile: name });
+                                }
+                                Err(e) => status.set(Some(Err(e.to_string()))),
+                            }
+                        }
+                    },
+                    "Browse"
+                }
+            }
+            if let Some(action) = pending() {
+                Confirm {
+                    on_confirm: move |()| {
+                        spawn(confirm(()));
+                    },
+                    on_cancel: move |()| pending.set(None),
+                    match action {
+                        Pending::Restore(id) => rsx! {
+                            "Restore backup {id}? Everything written since it was taken will be lost."
+                        },
+                        Pending::Purge(n) => rsx! { "Delete all but the newest {n} backups?" },
+                    }
+                }
+            }
+            StatusLine { status: status() }
+            if !verified.read().is_empty() {
+                ul {
+                    for (id, res) in verified() {
+                        li {
+                            match res {
+                                Ok(()) => rsx! { "backup {id}: ok" },
+                                Err(e) => rsx! { span { style: "color: red;", "backup {id}: {e}" } },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{Backups, TableSpec};
+#[cfg(feature = "server")]
+use crate::server::{auth::authorize, permission::Access, state::review, tables::format_time};
+
+/// The backup schedule of a profile, formatted for display.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ScheduleInfo {
+    every: String,
+    keep: u32,
+    window: Option<String>,
+    last_run: Option<String>,
+    /// Why the last run failed, if it did.
+    last_error: Option<String>,
+    next_run: Option<String>,
+}
+
+#[server]
+async fn backup_schedule(profile: String) -> Result<Option<ScheduleInfo>, ServerFnError> {
+    authorize(Backups::PERMISSION, Access::Read).await?;
+    let review = review(&profile).await?;
+    Ok(review.schedule().map(|status| {
+        let (last_run, last_error) = match status.last_run {
+            Some((time, result)) => (Some(format_time(time)), result.err()),
+            None => (None, None),
+        };
+        ScheduleInfo {
+            every: format_interval(status.interval.as_secs()),
+            keep: status.keep,
+            window: status.window.map(|w| format!("{w} UTC")),
+            last_run,
+            last_error,
+            next_run: status.next_run.map(format_time),
+        }
+    }))
+}
+
+/// Formats `secs` in the largest unit that divides it.
+#[cfg(feature = "server")]
+fn format_interval(secs: u64) -> String {
+    let (n, unit) = match secs {
+        0 => (0, "second"),
+        s if s % 86_400 == 0 => (s / 86_400, "day"),
+        s if s % 3_600 == 0 => (s / 3_600, "hour"),
+        s if s % 60 == 0 => (s / 60, "minute"),
+        s => (s, "second"),
+    };
+    if n == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{n} {unit}s")
+    }
+}
+
+/// Shows when the store was last backed up on schedule and when it will be
+/// next.
+#[component]
+pub(super) fn Schedule(profile: String) -> Element {
+    let schedule = use_server_future(use_reactive((&profile,), |(profile,)| {
+        backup_schedule(profile)
+    }))?;
+    rsx! {
+        section { style: "margin: 16px 0; max-width: 640px;",
+            h3 { style: "font: small-caps bold 18px sans-serif;", "Schedule" }
+            match schedule() {
+                None => rsx! { p { "Loading..." } },
+                Some(Err(e)) => rsx! { p { "{e}" } },
+                Some(Ok(None)) => rsx! { p { "No backups are scheduled for this profile." } },
+                Some(Ok(Some(schedule))) => rsx! {
+                    p {
+                        "Every {schedule.every}, keeping the newest {schedule.keep} backups"
+                        if let Some(window) = &schedule.window {
+                            ", starting between {window}"
+                        }
+                        "."
+                    }
+                    p {
+                        "Last run: "
+                        match (&schedule.last_run, &schedule.last_error) {
+                            (None, _) => rsx! { "none yet" },
+                            (Some(time), None) => rsx! { "{time} " span { color: "green", "(succeeded)" } },
+                            (Some(time), Some(e)) => rsx! { "{time} " span { color: "red", "(failed: {e})" } },
+                        }
+                    }
+                    p {
+                        "Next run: "
+                        {schedule.next_run.clone().unwrap_or_else(|| "none".to_string())}
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::format_interval;
+
+    #[test]
+    fn intervals() {
+        assert_eq!(format_interval(0), "0 seconds");
+        assert_eq!(format_interval(1), "1 second");
+        assert_eq!(format_interval(90), "90 seconds");
+        assert_eq!(format_interval(60), "1 minute");
+        assert_eq!(format_interval(300), "5 minutes");
+        assert_eq!(format_interval(3_600), "1 hour");
+        assert_eq!(format_interval(21_600), "6 hours");
+        assert_eq!(format_interval(86_400), "1 day");
+        assert_eq!(format_interval(604_800), "7 days");
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{anyhow, Context, Result};
+use dioxus_logger::tracing;
+use tokio_rustls::{
+    rustls::{
+        crypto::{ring, CryptoProvider},
+        pki_types::{CertificateDer, PrivateKeyDer},
+        server::WebPkiClientVerifier,
+        RootCertStore, ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+/// The TLS settings shared by all TCP listeners.
+///
+/// The certificate and key are read again from the same paths by
+/// [`Tls::reload`], so a rotated certificate takes effect for new connections
+/// without restarting Deview.
+#[derive(Clone)]
+pub(crate) struct Tls {
+    cert: PathBuf,
+    key: PathBuf,
+    ca_certs: Vec<PathBuf>,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl Tls {
+    /// Loads the certificate chain, private key and CA certificates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the files cannot be read or parsed.
+    pub(crate) fn new(cert: &Path, key: &Path, ca_certs: &[PathBuf]) -> Result<Self> {
+        let config = server_config(cert, key, ca_certs)?;
+        Ok(Self {
+            cert: cert.to_path_buf(),
+            key: key.to_path_buf(),
+            ca_certs: ca_certs.to_vec(),
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        })
+    }
+
+    pub(crate) fn acceptor(&self) -> TlsAcceptor {
+        let config = self.config.read().expect("TLS config lock poisoned");
+        TlsAcceptor::from(config.clone())
+    }
+
+    /// Re-reads the certificate files. The current configuration is kept if
+    /// the new files are invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the files cannot be read or parsed.
+    pub(crate) fn reload(&self) -> Result<()> {
+        let config = server_config(&self.cert, &self.key, &self.ca_certs)?;
+        *self.config.write().expect("TLS config lock poisoned") = Arc::new(config);
+        Ok(())
+    }
+
+    /// Reloads the certificate every time the process receives SIGHUP.
+    pub(crate) async fn reload_on_hangup(self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("cannot watch SIGHUP, certificate reload disabled: {e}");
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            match self.reload() {
+                Ok(()) => tracing::info!("reloaded certificate {}", self.cert.display()),
+                Err(e) => tracing::error!("failed to reload certificate: {e:#}"),
+            }
+        }
+    }
+}
+
+fn server_config(cert: &Path, key: &Path, ca_certs: &[PathBuf]) -> Result<ServerConfig> {
+    let provider = Arc::new(ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .context("cannot select TLS protocol versions")?;
+    let builder = if ca_certs.is_empty() {
+        builder.with_no_client_auth()
+    } else {
+        let mut roots = RootCertStore::empty();
+        for path in ca_certs {
+            for ca in read_certs(path)? {
+                roots
+                    .add(ca)
+                    .with_context(|| format!("invalid CA certificate in {}", path.display()))?;
+            }
+        }
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .context("cannot build the client certificate verifier")?;
+        builder.with_client_cert_verifier(verifier)
+    };
+    let mut config = builder
+        .with_single_cert(read_certs(cert)?, read_key(key)?)
+        .context("certificate and key do not match")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("cannot read certificates from {}", path.display()))?;
+    if certs.is_empty() {
+        return Err(anyhow!("no certificate found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+fn read_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("cannot read the private key from {}", path.display()))?
+        .ok_or_else(|| anyhow!("no private key found in {}", path.display()))
+}